@@ -1,9 +1,13 @@
 // src/card_deck.rs
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::fs;
+use std::path::Path;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
     Heart, Diamond, Club, Spade,
 }
@@ -19,11 +23,17 @@ impl fmt::Display for Suit {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Rank {
     Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace,
 }
 
+pub const ALL_SUITS: [Suit; 4] = [Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade];
+pub const ALL_RANKS: [Rank; 13] = [
+    Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven,
+    Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+];
+
 impl fmt::Display for Rank {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let display_str = match self {
@@ -48,6 +58,14 @@ impl Rank {
             Rank::Ace   => (11, true),
         }
     }
+    // Hi-Lo card counting tag: 2-6 = +1, 7-9 = 0, 10-A = -1
+    pub fn hi_lo_value(&self) -> i32 {
+        match self {
+            Rank::Two | Rank::Three | Rank::Four | Rank::Five | Rank::Six => 1,
+            Rank::Seven | Rank::Eight | Rank::Nine => 0,
+            _ => -1,
+        }
+    }
     // Value for pair checking (10 for face cards)
     pub fn blackjack_value(&self) -> u8 {
          match self {
@@ -58,7 +76,7 @@ impl Rank {
 }
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
@@ -70,44 +88,117 @@ impl fmt::Display for Card {
     }
 }
 
-#[derive(Debug)]
+impl Card {
+    // Parses "AS", "10h", "Td" or the displayed form "A♠" (case-insensitive)
+    pub fn parse(token: &str) -> Option<Card> {
+        let suit_char = token.chars().last()?;
+        let rank_str = token[..token.len() - suit_char.len_utf8()].to_ascii_uppercase();
+        let suit = match suit_char.to_ascii_uppercase() {
+            'H' | '♥' => Suit::Heart,
+            'D' | '♦' => Suit::Diamond,
+            'C' | '♣' => Suit::Club,
+            'S' | '♠' => Suit::Spade,
+            _ => return None,
+        };
+        let rank = match rank_str.as_str() {
+            "2" => Rank::Two, "3" => Rank::Three, "4" => Rank::Four, "5" => Rank::Five,
+            "6" => Rank::Six, "7" => Rank::Seven, "8" => Rank::Eight, "9" => Rank::Nine,
+            "10" | "T" => Rank::Ten, "J" => Rank::Jack, "Q" => Rank::Queen, "K" => Rank::King,
+            "A" => Rank::Ace,
+            _ => return None,
+        };
+        Some(Card { rank, suit })
+    }
+}
+
+// The shoe owns its RNGs so a saved session reshuffles (and AI seats err) exactly as it would have
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Deck {
     cards: Vec<Card>,
+    discards: Vec<Card>, // Dealt since the last shuffle; returned to the shoe on reshuffle
+    rng: ChaCha8Rng,
+    // Random decisions at the table (Sloppy AI seats); a separate stream so the same seed
+    // shuffles the same shoes whatever the AI seats do
+    #[serde(default = "entropy_rng")]
+    decision_rng: ChaCha8Rng,
     pub initial_size: usize,
+    #[serde(default)]
+    stacked: bool, // Dealt to the last card of the file, see from_cards
+    #[serde(default)]
+    round_start_discards: usize, // Discards before the current round; the rest are on the table
 }
 
 impl Deck {
-    pub fn new(num_decks: usize) -> Self {
+    // Same seed, same shuffles: lets a run be reproduced. None seeds from entropy.
+    pub fn new(num_decks: usize, seed: Option<u64>) -> Self {
         let mut cards = Vec::new();
-        let suits = [Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade];
-        let ranks = [
-            Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven,
-            Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
-        ];
-
         for _ in 0..num_decks {
-            for &suit_val in suits.iter() {
-                for &rank_val in ranks.iter() {
+            for &suit_val in ALL_SUITS.iter() {
+                for &rank_val in ALL_RANKS.iter() {
                     cards.push(Card { rank: rank_val, suit: suit_val });
                 }
             }
         }
         let initial_size = cards.len();
-        let mut deck = Deck { cards, initial_size };
+        let mut deck = Deck {
+            cards, discards: Vec::new(), rng: shoe_rng(seed), decision_rng: decision_rng(seed), initial_size,
+            stacked: false, round_start_discards: 0,
+        };
         deck.shuffle(); // Shuffle on creation
         deck
     }
 
+    // A stacked shoe: `cards_in_deal_order[0]` is the first card dealt. The whole sequence is
+    // dealt, ignoring the reshuffle threshold; only once it is used up are the discards reshuffled.
+    pub fn from_cards(cards_in_deal_order: Vec<Card>, seed: Option<u64>) -> Self {
+        let mut cards = cards_in_deal_order;
+        cards.reverse(); // deal() takes from the end
+        let initial_size = cards.len();
+        Deck {
+            cards, discards: Vec::new(), rng: shoe_rng(seed), decision_rng: decision_rng(seed), initial_size,
+            stacked: true, round_start_discards: 0,
+        }
+    }
+
     pub fn shuffle(&mut self) {
-        let mut rng = thread_rng();
-        self.cards.shuffle(&mut rng);
+        self.cards.shuffle(&mut self.rng);
+    }
+
+    // Gathers the discards back into the shoe and shuffles it
+    pub fn reshuffle(&mut self) {
+        self.cards.append(&mut self.discards);
+        self.round_start_discards = 0;
+        self.shuffle();
     }
 
+    // Called before each round is dealt, after any reshuffle
+    pub fn start_round(&mut self) {
+        self.round_start_discards = self.discards.len();
+    }
+
+    // A stacked shoe that runs out mid-round reshuffles the cards of earlier rounds,
+    // leaving the ones on the table out
     pub fn deal(&mut self) -> Option<Card> {
-        self.cards.pop()
+        if self.cards.is_empty() && self.stacked && self.round_start_discards > 0 {
+            log::info!("Stacked shoe used up mid-round; reshuffling the {} cards of earlier rounds.", self.round_start_discards);
+            self.cards.extend(self.discards.drain(..self.round_start_discards));
+            self.round_start_discards = 0;
+            self.shuffle();
+        }
+        let card = self.cards.pop()?;
+        self.discards.push(card);
+        Some(card)
     }
 
+    pub fn decision_rng(&mut self) -> &mut ChaCha8Rng {
+        &mut self.decision_rng
+    }
+
+    // A stacked shoe only needs one once every card of the file has been dealt
     pub fn needs_reshuffle(&self, threshold_ratio: f64) -> bool {
+        if self.stacked {
+            return self.cards.is_empty();
+        }
         (self.cards.len() as f64) < (self.initial_size as f64 * threshold_ratio)
     }
 
@@ -120,3 +211,40 @@ impl Deck {
         self.cards.is_empty()
     }
 }
+
+fn shoe_rng(seed: Option<u64>) -> ChaCha8Rng {
+    match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    }
+}
+
+fn decision_rng(seed: Option<u64>) -> ChaCha8Rng {
+    let mut rng = shoe_rng(seed);
+    rng.set_stream(1);
+    rng
+}
+
+// Saves from before the decision RNG existed
+fn entropy_rng() -> ChaCha8Rng {
+    ChaCha8Rng::from_entropy()
+}
+
+// Reads a shoe file: cards like "AS KH 7D" separated by spaces, commas or newlines, in deal order.
+// Anything after '#' on a line is a comment.
+pub fn load_shoe_file(path: &Path) -> Result<Vec<Card>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Cannot read shoe file {}: {}", path.display(), e))?;
+    let mut cards = Vec::new();
+    for (line_no, line) in contents.lines().enumerate() {
+        let without_comment = line.split('#').next().unwrap_or("");
+        for token in without_comment.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()) {
+            let card = Card::parse(token)
+                .ok_or_else(|| format!("{}:{}: '{}' is not a card (expected e.g. AS, 10H, TD, 7C)", path.display(), line_no + 1, token))?;
+            cards.push(card);
+        }
+    }
+    if cards.is_empty() {
+        return Err(format!("Shoe file {} has no cards.", path.display()));
+    }
+    Ok(cards)
+}
@@ -0,0 +1,328 @@
+// src/cli.rs
+use crate::config::{GraphFormat, LogFormat, Settings};
+use crate::money::Money;
+use crate::strategy::AiProfile;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+// Without a subcommand the program asks for a mode, as it always has.
+// Any value not given as a flag is asked for interactively (or taken from config.rs).
+#[derive(Debug, Parser)]
+#[command(name = "rust_blackjack_simulator", version, about = "Blackjack simulator, trainer and drills")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub table: TableArgs,
+
+    #[arg(long, global = true, value_name = "DIR", help = "Directory for logs, hand histories and graphs")]
+    pub output_dir: Option<String>,
+
+    #[arg(long, global = true, value_name = "NAME", help = "Log file name in the output directory; \"{run_id}\" is replaced by the RUN_ID")]
+    pub log_file: Option<String>,
+
+    #[arg(long, global = true, value_name = "FILE", help = "CSV results table to append a row to (default results.csv in the output directory)")]
+    pub results_table: Option<String>,
+
+    #[arg(long, global = true, value_name = "FILE", help = "SQLite results database (default results.sqlite in the output directory)")]
+    pub results_db: Option<String>,
+
+    #[arg(long, global = true, value_enum, help = "Log file format")]
+    pub log_format: Option<LogFormat>,
+
+    #[arg(long, global = true, value_enum, help = "Image format of the graphs")]
+    pub graph_format: Option<GraphFormat>,
+
+    #[arg(long, global = true, help = "Also draw the balance and round result charts as text in the terminal")]
+    pub terminal_charts: bool,
+
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL",
+          help = "Echo log records to the console (--log-stdout=false for file only)")]
+    pub log_stdout: Option<bool>,
+
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet", help = "More log output (-v debug, -vv trace)")]
+    pub verbose: u8,
+
+    #[arg(short, long, global = true, help = "Only log warnings and errors")]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Play interactively")]
+    Play(PlayArgs),
+    #[command(about = "Simulate \"Your\" play by the Book")]
+    Simulate(SimulateArgs),
+    #[command(about = "Summarize an exported hand history without stepping through it")]
+    Analyze(HistoryFileArgs),
+    #[command(about = "Strategy flash cards or a Hi-Lo counting drill")]
+    Drill(DrillArgs),
+    #[command(about = "Step through an exported hand history")]
+    Replay(HistoryFileArgs),
+    #[command(about = "Overlay the balance of several runs (from their hand histories) in one graph")]
+    Compare(CompareArgs),
+    #[command(about = "List, filter and compare runs stored in the results database")]
+    Runs(RunsArgs),
+    #[command(about = "List, reset or delete player profiles (asks when no action is given)")]
+    Profiles(ProfilesArgs),
+}
+
+// Rules, table and money flags; shared by every subcommand
+#[derive(Debug, Args)]
+pub struct TableArgs {
+    #[arg(long, global = true, help = "Number of decks in the shoe")]
+    pub decks: Option<usize>,
+
+    #[arg(long, global = true, value_name = "N:D", value_parser = parse_payout, help = "Blackjack payout, e.g. 3:2 or 6:5")]
+    pub payout: Option<(i64, i64)>,
+
+    #[arg(long, global = true, value_name = "RATIO", help = "Reshuffle when less than this fraction of the shoe is left")]
+    pub reshuffle_at: Option<f64>,
+
+    #[arg(long, global = true, help = "Seats at the table, including yours")]
+    pub players: Option<usize>,
+
+    #[arg(long, global = true, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+          help = "Your seat, 1 = first base")]
+    pub seat: Option<usize>,
+
+    #[arg(long, global = true, value_name = "SEATS", value_delimiter = ',',
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+          help = "Seats left empty, e.g. 2,4 (1 = first base)")]
+    pub empty_seats: Option<Vec<usize>>,
+
+    #[arg(long = "ai-seat", global = true, value_name = "SEAT=PROFILE", value_parser = parse_ai_seat,
+          help = "Behaviour of an AI seat (repeat the flag): book, never-bust, mimic-dealer, sloppy:RATE (e.g. sloppy:0.05) or tourist")]
+    pub ai_seats: Vec<(usize, AiProfile)>,
+
+    #[arg(long, global = true, value_name = "AMOUNT", value_parser = parse_money, help = "Starting balance in dollars")]
+    pub bankroll: Option<Money>,
+
+    #[arg(long, global = true, value_name = "AMOUNT", value_parser = parse_money, help = "Default bet in dollars")]
+    pub bet: Option<Money>,
+
+    #[arg(long, global = true, help = "Seed for the shoe shuffle, to reproduce a run")]
+    pub seed: Option<u64>,
+
+    #[arg(long, global = true, value_name = "FILE", help = "Deal this card sequence instead of a shuffled shoe (e.g. \"AS KH 7D ...\")")]
+    pub shoe: Option<String>,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct PlayArgs {
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", help = "Practice mode: allow undo/redo (asked if omitted; --practice=false for no)")]
+    pub practice: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", help = "Trainer mode: grade decisions against the Book (asked if omitted)")]
+    pub trainer: Option<bool>,
+
+    #[command(flatten)]
+    pub profile: ProfileArgs,
+
+    #[arg(long, help = "Resume a saved session instead of starting a new one")]
+    pub resume: bool,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct SimulateArgs {
+    #[arg(short = 'n', long, value_parser = clap::value_parser!(u32).range(1..), help = "Number of hands to simulate")]
+    pub iterations: Option<u32>,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..),
+          help = "Hands per session for the session outcome chart and stop-win/stop-loss counts (default 100)")]
+    pub session_hands: Option<u32>,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct ProfileArgs {
+    #[arg(long, value_name = "NAME", conflicts_with = "no_profile", help = "Player profile to load or create")]
+    pub profile: Option<String>,
+
+    #[arg(long, help = "Play without a profile")]
+    pub no_profile: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DrillKind {
+    Strategy,
+    Counting,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct DrillArgs {
+    #[arg(help = "Which drill to run")]
+    pub kind: Option<DrillKind>,
+
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), help = "Strategy drill: number of flash cards")]
+    pub questions: Option<u32>,
+
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), help = "Counting drill: cards between count checks")]
+    pub interval: Option<u32>,
+
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), help = "Counting drill: number of count checks")]
+    pub checks: Option<u32>,
+
+    #[command(flatten)]
+    pub profile: ProfileArgs,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct HistoryFileArgs {
+    #[arg(help = "Hand history file (_hands.jsonl); asked for if omitted")]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    #[arg(required = true, num_args = 1.., help = "Hand history files (_hands.jsonl) to compare")]
+    pub files: Vec<PathBuf>,
+
+    #[arg(long = "label", value_name = "LABEL",
+          help = "Legend label per file, in order (repeat the flag); defaults to the run's rules. Files with the same label form a group")]
+    pub labels: Vec<String>,
+
+    #[arg(long, help = "Draw each group's mean with a 10th-90th percentile band instead of every run")]
+    pub bands: bool,
+
+    #[arg(long, value_name = "FILE", help = "Output image (default compare_<RUN_ID>.png in the output directory)")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct RunsArgs {
+    #[command(subcommand)]
+    pub command: RunsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RunsCommand {
+    #[command(about = "List stored runs, newest first")]
+    List(RunsListArgs),
+    #[command(about = "Show the key metrics of two runs side by side")]
+    Diff {
+        #[arg(help = "RUN_ID of the first run")]
+        run_a: u64,
+        #[arg(help = "RUN_ID of the second run")]
+        run_b: u64,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct RunsListArgs {
+    #[arg(long, value_name = "TEXT", help = "Only runs whose rules contain this, e.g. \"6:5\" or \"2D\"")]
+    pub rules: Option<String>,
+
+    #[arg(long, value_name = "TEXT", help = "Only runs whose mode contains this, e.g. Simulation or Interactive")]
+    pub mode: Option<String>,
+
+    #[arg(long, value_name = "DATE", help = "Only runs started on or after this date (YYYY-MM-DD, UTC)")]
+    pub since: Option<String>,
+
+    #[arg(long, default_value_t = 20, help = "Most runs to list")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct ProfilesArgs {
+    #[command(subcommand)]
+    pub command: Option<ProfilesCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProfilesCommand {
+    #[command(about = "Show every saved profile")]
+    List,
+    #[command(about = "Start a profile over from the starting balance, clearing its stats")]
+    Reset {
+        #[arg(help = "Profile name")]
+        name: String,
+    },
+    #[command(about = "Delete a profile")]
+    Delete {
+        #[arg(help = "Profile name")]
+        name: String,
+    },
+}
+
+fn parse_payout(s: &str) -> Result<(i64, i64), String> {
+    let (numerator, denominator) = s.split_once(':').ok_or("expected N:D, e.g. 3:2")?;
+    let parse = |v: &str| v.trim().parse::<i64>().map_err(|_| format!("'{}' is not a whole number", v));
+    Ok((parse(numerator)?, parse(denominator)?))
+}
+
+fn parse_ai_seat(s: &str) -> Result<(usize, AiProfile), String> {
+    let (seat, profile) = s.split_once('=').ok_or("expected SEAT=PROFILE, e.g. 3=never-bust")?;
+    let seat = match seat.trim().parse::<usize>() {
+        Ok(seat) if seat >= 1 => seat - 1,
+        _ => return Err(format!("'{}' is not a seat number (1 = first base)", seat)),
+    };
+    let profile = match profile.trim().to_ascii_lowercase().as_str() {
+        "book" => AiProfile::Book,
+        "never-bust" => AiProfile::NeverBust,
+        "mimic-dealer" => AiProfile::MimicDealer,
+        "tourist" => AiProfile::Tourist,
+        other => match other.strip_prefix("sloppy:").map(|rate| rate.parse::<f64>()) {
+            Some(Ok(error_rate)) => AiProfile::Sloppy { error_rate },
+            Some(Err(_)) => return Err(format!("'{}' is not an error rate (e.g. sloppy:0.05)", other)),
+            None => return Err(format!("unknown profile '{}' (book, never-bust, mimic-dealer, sloppy:RATE, tourist)", other)),
+        },
+    };
+    Ok((seat, profile))
+}
+
+fn parse_money(s: &str) -> Result<Money, String> {
+    Money::parse(s).ok_or_else(|| format!("'{}' is not an amount (e.g. 25 or 12.50)", s))
+}
+
+impl Cli {
+    // Defaults from config.rs with any flags applied on top
+    pub fn settings(&self) -> Settings {
+        let mut settings = Settings::default();
+        let table = &self.table;
+        if let Some(decks) = table.decks { settings.num_decks = decks; }
+        if let Some((numerator, denominator)) = table.payout {
+            settings.blackjack_payout_numerator = numerator;
+            settings.blackjack_payout_denominator = denominator;
+        }
+        if let Some(ratio) = table.reshuffle_at { settings.reshuffle_threshold_ratio = ratio; }
+        if let Some(players) = table.players { settings.num_players = players; }
+        if let Some(seat) = table.seat { settings.your_seat_index = seat - 1; }
+        if let Some(seats) = &table.empty_seats { settings.empty_seats = seats.iter().map(|s| s - 1).collect(); }
+        if !table.ai_seats.is_empty() { settings.ai_seat_profiles = table.ai_seats.clone(); }
+        if let Some(bankroll) = table.bankroll { settings.starting_balance = bankroll; }
+        if let Some(bet) = table.bet { settings.default_bet = bet; }
+        if table.seed.is_some() { settings.seed = table.seed; }
+        if table.shoe.is_some() { settings.stacked_shoe_file = table.shoe.clone(); }
+        if let Some(dir) = &self.output_dir { settings.output_dir = dir.clone(); }
+        if let Some(name) = &self.log_file { settings.log_filename = name.clone(); }
+        if let Some(table) = &self.results_table { settings.results_table = table.clone(); }
+        if let Some(db) = &self.results_db { settings.results_db = db.clone(); }
+        if let Some(format) = self.log_format { settings.log_format = format; }
+        if let Some(format) = self.graph_format { settings.graph_format = format; }
+        if self.terminal_charts { settings.terminal_charts = true; }
+        if let Some(to_stdout) = self.log_stdout { settings.log_to_stdout = to_stdout; }
+        settings.log_level = match (self.quiet, self.verbose) {
+            (true, _) => log::LevelFilter::Warn,
+            (false, 0) => log::LevelFilter::Info,
+            (false, 1) => log::LevelFilter::Debug,
+            (false, _) => log::LevelFilter::Trace,
+        };
+        settings
+    }
+}
+
+// The mode menu shown when no subcommand is given, mapped onto the subcommands
+pub fn prompt_for_command() -> Command {
+    let choice = crate::utils::get_user_input(
+        "Choose mode: (i)nteractive, (s)imulation (for 'Your' play), (d)rill, (p)rofiles, (r)esume saved game or (h)and history replay? ",
+    );
+    match choice.as_str() {
+        "s" => Command::Simulate(SimulateArgs::default()),
+        "d" => Command::Drill(DrillArgs::default()),
+        "p" => Command::Profiles(ProfilesArgs::default()),
+        "r" => Command::Play(PlayArgs { resume: true, ..PlayArgs::default() }),
+        "h" => Command::Replay(HistoryFileArgs::default()),
+        _ => Command::Play(PlayArgs::default()),
+    }
+}
@@ -1,19 +1,228 @@
 // src/config.rs
+use crate::money::{smallest_chip, Money};
+use crate::strategy::AiProfile;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// The constants below are defaults. Those that can be overridden from the command line
+// (see cli.rs) are read at runtime through settings().
 
 pub const NUM_DECKS: usize = 6;
 pub const RESHUFFLE_THRESHOLD_RATIO: f64 = 0.25;
-pub const NUM_PLAYERS: usize = 3;
+// Deal from a recorded or hand-crafted card sequence instead of a shuffled shoe,
+// e.g. Some("shoes/casino_night.txt"). Format: see card_deck::load_shoe_file.
+pub const STACKED_SHOE_FILE: Option<&str> = None;
+pub const NUM_PLAYERS: usize = 3; // Seats at the table (incl. You and any empty seats)
+pub const MAX_SEATS: usize = 7;
+
+// Seat indices run from first base (0, dealt first) to third base (NUM_PLAYERS - 1, dealt last)
+pub const YOUR_SEAT_INDEX: usize = 0;
+pub const EMPTY_SEATS: &[usize] = &[];
+
+// Behaviour of AI seats, by seat index. Seats not listed play by the Book.
+// e.g. &[(1, AiProfile::NeverBust), (2, AiProfile::Sloppy { error_rate: 0.05 })]
+pub const AI_SEAT_PROFILES: &[(usize, AiProfile)] = &[];
 
-pub const STARTING_BALANCE: f64 = 1000.00;
-pub const DEFAULT_BET: f64 = 25.00;
-pub const MIN_BET_ALLOWED: f64 = 1.00;
+pub const STARTING_BALANCE: Money = Money::from_dollars(1000);
+pub const DEFAULT_BET: Money = Money::from_dollars(25);
+pub const MIN_BET_ALLOWED: Money = Money::from_dollars(1);
 
-pub const BLACKJACK_PAYOUT_NUMERATOR: f64 = 6.0;
-pub const BLACKJACK_PAYOUT_DENOMINATOR: f64 = 5.0;
-pub const BLACKJACK_PAYOUT_MULTIPLIER: f64 = BLACKJACK_PAYOUT_NUMERATOR / BLACKJACK_PAYOUT_DENOMINATOR;
+// Chips available at the table, smallest first. Bets must be a multiple of the
+// smallest chip, and blackjack payouts are rounded down to it.
+pub const CHIP_DENOMINATIONS: &[Money] = &[
+    Money::from_dollars(1), Money::from_dollars(5), Money::from_dollars(25),
+    Money::from_dollars(100), Money::from_dollars(500),
+];
+
+pub const BLACKJACK_PAYOUT_NUMERATOR: i64 = 6;
+pub const BLACKJACK_PAYOUT_DENOMINATOR: i64 = 5;
+
+// Short description of the table rules, used to group results by rule set
+pub fn rules_summary() -> String {
+    let s = settings();
+    format!("{}D, BJ {}:{}, Dealer stands on 17, DAS", s.num_decks, s.blackjack_payout_numerator, s.blackjack_payout_denominator)
+}
 
 pub const DEFAULT_SIM_ITERATIONS: u32 = 1000;
+// A simulation is cut into sessions of this many hands for the session outcome histogram
+// and the stop-win/stop-loss counts
+pub const SESSION_LENGTH_HANDS: u32 = 100;
+// Stop-win/stop-loss thresholds, in default bets, checked within each of those sessions to see
+// how often a player quitting at them would have left up or down
+pub const STOP_WIN_BETS: i64 = 10;
+pub const STOP_LOSS_BETS: i64 = 10;
+// Target half-width of the 95% confidence interval on EV per initial bet, in percent,
+// used to report how many hands a simulation needs
+pub const TARGET_EV_PRECISION_PCT: f64 = 0.1;
+
+// EV matrix: cells need this many rounds before they are listed among the costliest
+pub const EV_MATRIX_MIN_ROUNDS: u32 = 30;
+pub const EV_MATRIX_COSTLIEST_CELLS: usize = 5;
+
+// Drill mode
+pub const DEFAULT_DRILL_QUESTIONS: u32 = 20;
+pub const DRILL_MISS_WEIGHT: u32 = 4; // Extra weight per miss when picking the next flash card
+pub const DEFAULT_COUNT_DRILL_INTERVAL: usize = 10; // Cards flipped between count checks
+pub const DEFAULT_COUNT_DRILL_CHECKS: u32 = 10;
+
+// Trainer mode: Monte Carlo trials per action when estimating the EV cost of a mistake
+pub const TRAINER_EV_TRIALS: u32 = 20_000;
+
+// Hand history replay and the interactive balance graph: a round is a "large swing" (big win/loss)
+// when Your balance moved by at least this many default bets
+pub const REPLAY_LARGE_SWING_BETS: i64 = 2;
 
 pub const LOGS_DIR_NAME: &str = "logs";
-pub const TEXT_LOG_FILENAME: &str = "results.log";
-pub const GRAPH_EXTENSION: &str = "png";
+pub const PROFILES_DIR_NAME: &str = "profiles";
+pub const SAVES_DIR_NAME: &str = "saves"; // Saved interactive sessions, resumable mid-shoe
+pub const TEXT_LOG_FILENAME: &str = "results.log"; // "{run_id}" in the name is replaced by the run's RUN_ID
+pub const LOG_TO_STDOUT: bool = true; // Echo log records to the console as well as the log file
+pub const LOG_FORMAT: LogFormat = LogFormat::Text;
+pub const GRAPH_FORMAT: GraphFormat = GraphFormat::Png;
+// Also draw the balance trajectory and round result histogram as text at the end of a session,
+// for terminals without access to the image files
+pub const TERMINAL_CHARTS: bool = false;
+pub const TERMINAL_CHART_WIDTH: usize = 72; // Columns of the plot area
+pub const TERMINAL_CHART_HEIGHT: usize = 15; // Rows of the balance chart
+pub const HAND_HISTORY_SUFFIX: &str = "_hands.jsonl"; // logs/<RUN_ID>_hands.jsonl, one JSON record per round
+pub const EV_MATRIX_SUFFIX: &str = "_ev_matrix.csv"; // logs/<RUN_ID>_ev_matrix.csv, one row per chart cell
+pub const REPORT_SUFFIX: &str = "_report.json"; // logs/<RUN_ID>_report.json, the session report
+pub const HTML_REPORT_SUFFIX: &str = "_report.html"; // logs/<RUN_ID>_report.html, the report with its graphs embedded
+pub const RESULTS_TABLE_FILENAME: &str = "results.csv"; // One row per finished session
+pub const RESULTS_DB_FILENAME: &str = "results.sqlite"; // Finished sessions and their rounds, see results_db.rs
+
+// Format of the records written to the log file; the console echo is always text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    Text,
+    Json, // One JSON object per line: timestamp, level, run_id, target, message
+}
+
+// Image format of the graphs; SVG scales cleanly and is embedded as-is in the HTML report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    Png,
+    Svg,
+}
+
+impl GraphFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Png => "png",
+            GraphFormat::Svg => "svg",
+        }
+    }
+}
+
+// Runtime values for the settings that have command-line flags
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub num_decks: usize,
+    pub reshuffle_threshold_ratio: f64,
+    pub blackjack_payout_numerator: i64,
+    pub blackjack_payout_denominator: i64,
+    pub num_players: usize,
+    pub your_seat_index: usize,
+    pub empty_seats: Vec<usize>,
+    pub ai_seat_profiles: Vec<(usize, AiProfile)>,
+    pub starting_balance: Money,
+    pub default_bet: Money,
+    pub seed: Option<u64>, // Shoe shuffle seed; None shuffles from entropy
+    pub stacked_shoe_file: Option<String>,
+    pub output_dir: String,
+    pub log_filename: String,
+    pub results_table: String,
+    pub results_db: String,
+    pub log_level: log::LevelFilter,
+    pub log_to_stdout: bool,
+    pub log_format: LogFormat,
+    pub graph_format: GraphFormat,
+    pub terminal_charts: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            num_decks: NUM_DECKS,
+            reshuffle_threshold_ratio: RESHUFFLE_THRESHOLD_RATIO,
+            blackjack_payout_numerator: BLACKJACK_PAYOUT_NUMERATOR,
+            blackjack_payout_denominator: BLACKJACK_PAYOUT_DENOMINATOR,
+            num_players: NUM_PLAYERS,
+            your_seat_index: YOUR_SEAT_INDEX,
+            empty_seats: EMPTY_SEATS.to_vec(),
+            ai_seat_profiles: AI_SEAT_PROFILES.to_vec(),
+            starting_balance: STARTING_BALANCE,
+            default_bet: DEFAULT_BET,
+            seed: None,
+            stacked_shoe_file: STACKED_SHOE_FILE.map(str::to_string),
+            output_dir: LOGS_DIR_NAME.to_string(),
+            log_filename: TEXT_LOG_FILENAME.to_string(),
+            results_table: RESULTS_TABLE_FILENAME.to_string(),
+            results_db: RESULTS_DB_FILENAME.to_string(),
+            log_level: log::LevelFilter::Info,
+            log_to_stdout: LOG_TO_STDOUT,
+            log_format: LOG_FORMAT,
+            graph_format: GRAPH_FORMAT,
+            terminal_charts: TERMINAL_CHARTS,
+        }
+    }
+}
+
+impl Settings {
+    // Seat layout is checked separately by TableLayout::validate
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_decks == 0 {
+            return Err("Number of decks must be at least 1.".to_string());
+        }
+        if !(self.reshuffle_threshold_ratio > 0.0 && self.reshuffle_threshold_ratio < 1.0) {
+            return Err(format!("Reshuffle threshold ({}) must be between 0 and 1.", self.reshuffle_threshold_ratio));
+        }
+        if self.blackjack_payout_numerator <= 0 || self.blackjack_payout_denominator <= 0 {
+            return Err(format!("Blackjack payout {}:{} must be positive.",
+                self.blackjack_payout_numerator, self.blackjack_payout_denominator));
+        }
+        if self.default_bet < MIN_BET_ALLOWED {
+            return Err(format!("Default bet (${}) is below the minimum bet (${}).", self.default_bet, MIN_BET_ALLOWED));
+        }
+        if self.starting_balance < MIN_BET_ALLOWED {
+            return Err(format!("Starting balance (${}) is below the minimum bet (${}).", self.starting_balance, MIN_BET_ALLOWED));
+        }
+        let chip_unit = smallest_chip(CHIP_DENOMINATIONS);
+        if !self.default_bet.is_multiple_of(chip_unit) || !MIN_BET_ALLOWED.is_multiple_of(chip_unit) {
+            return Err(format!("Default bet (${}) and min bet (${}) must be multiples of the smallest chip (${}).",
+                self.default_bet, MIN_BET_ALLOWED, chip_unit));
+        }
+        if self.log_filename.trim().is_empty() {
+            return Err("Log file name must not be empty.".to_string());
+        }
+        Ok(())
+    }
+
+    // The log file for a run; a relative name is placed in the output directory
+    pub fn log_file_path(&self, run_id: u64) -> PathBuf {
+        PathBuf::from(&self.output_dir).join(self.log_filename.replace("{run_id}", &run_id.to_string()))
+    }
+
+    // Relative to the output directory unless absolute, so separate runs can share one table
+    pub fn results_table_path(&self) -> PathBuf {
+        PathBuf::from(&self.output_dir).join(&self.results_table)
+    }
+
+    // Like the results table, so runs with separate output directories can share a database
+    pub fn results_db_path(&self) -> PathBuf {
+        PathBuf::from(&self.output_dir).join(&self.results_db)
+    }
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+// Called once from main after parsing the command line; later calls are ignored
+pub fn install_settings(settings: Settings) {
+    if SETTINGS.set(settings).is_err() {
+        log::warn!("Settings were already installed; ignoring the new ones.");
+    }
+}
+
+pub fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
@@ -0,0 +1,262 @@
+// src/drill.rs
+use crate::card_deck::{Card, Deck, Rank, Suit, ALL_SUITS};
+use crate::cli::{DrillArgs, DrillKind};
+use crate::config;
+use crate::strategy::{get_basic_strategy_action, PlayerAction};
+use crate::trainer::{classify_hand, upcard_label, HandKind};
+use crate::utils;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+
+// One strategy chart cell: hand kind, player total (or pair card value), dealer upcard value
+pub type DrillCell = (HandKind, u8, u8);
+
+#[derive(Debug, Clone)]
+pub struct DrillReport {
+    pub name: String,
+    pub questions: u32,
+    pub correct: u32,
+    pub total_answer_seconds: f64,
+    pub misses_per_cell: BTreeMap<DrillCell, u32>, // Strategy drill only; added to the player's profile
+}
+
+impl DrillReport {
+    fn new(name: &str) -> Self {
+        DrillReport { name: name.to_string(), questions: 0, correct: 0, total_answer_seconds: 0.0, misses_per_cell: BTreeMap::new() }
+    }
+
+    pub fn accuracy_pct(&self) -> f64 {
+        if self.questions > 0 { self.correct as f64 / self.questions as f64 * 100.0 } else { 0.0 }
+    }
+
+    pub fn to_log_lines(&self) -> Vec<String> {
+        let avg_seconds = if self.questions > 0 { self.total_answer_seconds / self.questions as f64 } else { 0.0 };
+        vec![
+            format!("--- {} Drill Report ---", self.name),
+            format!("Questions: {}, Correct: {} ({:.1}%)", self.questions, self.correct, self.accuracy_pct()),
+            format!("Average Answer Time: {:.2} seconds", avg_seconds),
+        ]
+    }
+}
+
+// e.g. "Hard 12 vs 3", "Pair A vs 10"
+pub fn cell_label(cell: DrillCell) -> String {
+    let (kind, total, upcard) = cell;
+    let hand_label = if kind == HandKind::Pair { upcard_label(total) } else { total.to_string() };
+    format!("{} {} vs {}", kind, hand_label, upcard_label(upcard))
+}
+
+// Every two-card starting hand cell in the chart, crossed with every upcard
+pub fn all_drill_cells() -> Vec<DrillCell> {
+    let mut cells = Vec::new();
+    for upcard in 2..=11u8 {
+        for total in 5..=19u8 { cells.push((HandKind::Hard, total, upcard)); } // Non-pair hard two-card totals
+        for total in 13..=20u8 { cells.push((HandKind::Soft, total, upcard)); } // A,2 .. A,9
+        for pair_value in 2..=11u8 { cells.push((HandKind::Pair, pair_value, upcard)); }
+    }
+    cells
+}
+
+// The first rank of that value: Ten for 10
+fn first_rank_for_value(value: u8) -> Rank {
+    match value {
+        2 => Rank::Two, 3 => Rank::Three, 4 => Rank::Four, 5 => Rank::Five,
+        6 => Rank::Six, 7 => Rank::Seven, 8 => Rank::Eight, 9 => Rank::Nine,
+        10 => Rank::Ten,
+        _ => Rank::Ace,
+    }
+}
+
+fn rank_for_value(value: u8, rng: &mut impl Rng) -> Rank {
+    match value {
+        10 => *[Rank::Ten, Rank::Jack, Rank::Queen, Rank::King].choose(rng).unwrap_or(&Rank::Ten),
+        _ => first_rank_for_value(value),
+    }
+}
+
+fn card_for_value(value: u8, rng: &mut impl Rng) -> Card {
+    Card { rank: rank_for_value(value, rng), suit: ALL_SUITS[rng.gen_range(0..ALL_SUITS.len())] }
+}
+
+// Values of the first card of a non-pair two-card hard total, smallest first
+fn hard_first_card_values(total: u8) -> Vec<u8> {
+    (2..=10u8).filter(|&c| total > c && (2..=10).contains(&(total - c)) && total - c != c).collect()
+}
+
+// Deals a random two-card hand and upcard that fall in `cell`
+pub fn cards_for_cell(cell: DrillCell, rng: &mut impl Rng) -> (Vec<Card>, Card) {
+    let (kind, total, upcard_value) = cell;
+    let player_cards = match kind {
+        HandKind::Pair => vec![card_for_value(total, rng), card_for_value(total, rng)],
+        HandKind::Soft => vec![card_for_value(11, rng), card_for_value(total - 11, rng)],
+        HandKind::Hard => {
+            let first = *hard_first_card_values(total).choose(rng).unwrap_or(&2);
+            vec![card_for_value(first, rng), card_for_value(total - first, rng)]
+        }
+    };
+    (player_cards, card_for_value(upcard_value, rng))
+}
+
+// The same cell's first composition (smallest first card, Ten for 10, spades), so charts
+// drawn from it come out the same every time
+pub fn first_cards_for_cell(cell: DrillCell) -> (Vec<Card>, Card) {
+    let (kind, total, upcard_value) = cell;
+    let card = |value: u8| Card { rank: first_rank_for_value(value), suit: Suit::Spade };
+    let player_cards = match kind {
+        HandKind::Pair => vec![card(total), card(total)],
+        HandKind::Soft => vec![card(11), card(total - 11)],
+        HandKind::Hard => {
+            let first = hard_first_card_values(total).first().copied().unwrap_or(2);
+            vec![card(first), card(total - first)]
+        }
+    };
+    (player_cards, card(upcard_value))
+}
+
+// Flash cards: random starting hands vs upcards, weighted toward the cells answered wrong
+// most often, counting `past_misses` (from the player's profile) and this drill's misses
+pub fn run_strategy_drill(num_questions: u32, past_misses: &BTreeMap<DrillCell, u32>) -> DrillReport {
+    let mut rng = rand::thread_rng();
+    let cells = all_drill_cells();
+    let mut tally_per_kind: HashMap<HandKind, (u32, u32)> = HashMap::new();
+    let mut report = DrillReport::new("Basic Strategy");
+
+    println!("\n--- Basic Strategy Drill: {} hands. Answer (h)it, (s)tand, (d)ouble, s(p)lit, or (q)uit. ---", num_questions);
+    if !past_misses.is_empty() {
+        println!("Weighted toward the {} cells you missed in earlier drills.", past_misses.len());
+    }
+    for question in 1..=num_questions {
+        let misses = |c: &DrillCell| past_misses.get(c).copied().unwrap_or(0) + report.misses_per_cell.get(c).copied().unwrap_or(0);
+        let cell = *cells
+            .choose_weighted(&mut rng, |c| 1 + config::DRILL_MISS_WEIGHT * misses(c))
+            .unwrap_or(&cells[0]);
+        let (player_cards, upcard) = cards_for_cell(cell, &mut rng);
+        let book_action = get_basic_strategy_action(&player_cards, upcard.rank.value().0, 1, false);
+
+        let cards_str: Vec<String> = player_cards.iter().map(|c| c.to_string()).collect();
+        let started = Instant::now();
+        let answer = utils::get_user_input(&format!("#{} Your hand: {} vs Dealer {}: ", question, cards_str.join(" "), upcard));
+        let answer_seconds = started.elapsed().as_secs_f64();
+        let answered_action = match answer.as_str() {
+            "h" => Some(PlayerAction::Hit),
+            "s" => Some(PlayerAction::Stand),
+            "d" => Some(PlayerAction::Double),
+            "p" => Some(PlayerAction::Split),
+            "q" => break,
+            _ => None,
+        };
+        let is_correct = answered_action == Some(book_action);
+
+        report.questions += 1;
+        report.total_answer_seconds += answer_seconds;
+        let kind_tally = tally_per_kind.entry(classify_hand(&player_cards)).or_insert((0, 0));
+        kind_tally.1 += 1;
+        if is_correct {
+            report.correct += 1;
+            kind_tally.0 += 1;
+            println!("Correct ({:.1}s).", answer_seconds);
+        } else {
+            *report.misses_per_cell.entry(cell).or_insert(0) += 1;
+            if answered_action.is_none() { println!("'{}' is not an action.", answer); }
+            println!("Wrong. Book says {} ({:.1}s).", book_action, answer_seconds);
+        }
+    }
+
+    let mut lines = report.to_log_lines();
+    for kind in [HandKind::Hard, HandKind::Soft, HandKind::Pair] {
+        if let Some(&(correct, total)) = tally_per_kind.get(&kind) {
+            lines.push(format!("{} Hands: {}/{} correct", kind, correct, total));
+        }
+    }
+    let mut missed_cells: Vec<(&DrillCell, &u32)> = report.misses_per_cell.iter().collect();
+    missed_cells.sort_by(|a, b| b.1.cmp(a.1));
+    for (&cell, misses) in missed_cells.into_iter().take(5) {
+        lines.push(format!("Most Missed: {} ({} misses)", cell_label(cell), misses));
+    }
+    for line in lines {
+        println!("{}", line);
+        log::info!("{}", line);
+    }
+    report
+}
+
+// Flips cards from a shoe and asks for the Hi-Lo running and true count every `interval` cards
+pub fn run_counting_drill(num_decks: usize, interval: usize, num_checks: u32) -> DrillReport {
+    let mut deck = Deck::new(num_decks, config::settings().seed);
+    let mut running_count: i32 = 0;
+    let mut report = DrillReport::new("Hi-Lo Counting");
+
+    println!("\n--- Counting Drill: {} deck(s), count check every {} cards. Enter (q) to quit. ---", num_decks, interval);
+    println!("Hi-Lo: 2-6 = +1, 7-9 = 0, 10-A = -1. True count = running count / decks remaining.");
+    'checks: for check in 1..=num_checks {
+        let mut flipped: Vec<String> = Vec::new();
+        for _ in 0..interval {
+            match deck.deal() {
+                Some(card) => {
+                    running_count += card.rank.hi_lo_value();
+                    flipped.push(card.to_string());
+                }
+                None => break,
+            }
+        }
+        if flipped.is_empty() {
+            println!("Shoe finished.");
+            break;
+        }
+        println!("\nCheck #{} cards: {}", check, flipped.join(" "));
+        let decks_remaining = (deck.len() as f64 / 52.0).max(0.5); // Never divide by less than half a deck
+        let true_count = running_count as f64 / decks_remaining;
+
+        for (label, expected, tolerance) in [("Running count", running_count as f64, 0.0), ("True count", true_count, 0.5)] {
+            let started = Instant::now();
+            let answer = utils::get_user_input(&format!("{} ({:.1} decks left)? ", label, decks_remaining));
+            let answer_seconds = started.elapsed().as_secs_f64();
+            if answer == "q" { break 'checks; }
+            report.questions += 1;
+            report.total_answer_seconds += answer_seconds;
+            match answer.parse::<f64>() {
+                Ok(value) if (value - expected).abs() <= tolerance => {
+                    report.correct += 1;
+                    println!("Correct ({:.1}s).", answer_seconds);
+                }
+                _ => println!("Wrong. {} is {:.1} ({:.1}s).", label, expected, answer_seconds),
+            }
+        }
+    }
+
+    for line in report.to_log_lines() {
+        println!("{}", line);
+        log::info!("{}", line);
+    }
+    report
+}
+
+// Runs the drill given on the command line, asking for whatever was left out;
+// returns the report of the drill that ran
+pub fn run_drill_menu(args: &DrillArgs, past_misses: &BTreeMap<DrillCell, u32>) -> DrillReport {
+    let kind = args.kind.unwrap_or_else(|| {
+        match utils::get_user_input("Choose drill: (s)trategy flash cards or (c)ounting? ").as_str() {
+            "c" => DrillKind::Counting,
+            _ => DrillKind::Strategy,
+        }
+    });
+    match kind {
+        DrillKind::Counting => {
+            let interval = args.interval.unwrap_or_else(|| utils::get_positive_number_input(
+                "Cards between count checks", config::DEFAULT_COUNT_DRILL_INTERVAL as u32,
+            ));
+            let num_checks = args.checks.unwrap_or_else(|| {
+                utils::get_positive_number_input("Number of count checks", config::DEFAULT_COUNT_DRILL_CHECKS)
+            });
+            run_counting_drill(config::settings().num_decks, interval as usize, num_checks)
+        }
+        DrillKind::Strategy => {
+            let num_questions = args.questions.unwrap_or_else(|| {
+                utils::get_positive_number_input("Number of flash cards", config::DEFAULT_DRILL_QUESTIONS)
+            });
+            run_strategy_drill(num_questions, past_misses)
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// src/ev_matrix.rs
+use crate::card_deck::Card;
+use crate::config;
+use crate::hand::{Hand, HandEvent};
+use crate::money::Money;
+use crate::strategy::{calculate_value_for_strategy, PlayerAction};
+use crate::trainer::{classify_hand, upcard_label, HandKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+// A strategy chart cell: "Your" starting hand against the dealer upcard.
+// `total` is the hand value for Hard/Soft rows and the card value for Pair rows (Ace = 11).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ChartCell {
+    pub kind: HandKind,
+    pub total: u8,
+    pub upcard: u8, // 2-10, Ace = 11
+}
+
+impl ChartCell {
+    pub fn of(starting_cards: &[Card], upcard: Card) -> Self {
+        let kind = classify_hand(starting_cards);
+        let total = match kind {
+            HandKind::Pair => starting_cards[0].rank.blackjack_value(),
+            _ => calculate_value_for_strategy(starting_cards),
+        };
+        ChartCell { kind, total, upcard: upcard.rank.value().0 }
+    }
+
+    pub fn row_label(&self) -> String {
+        row_label(self.kind, self.total)
+    }
+}
+
+pub fn row_label(kind: HandKind, total: u8) -> String {
+    match kind {
+        HandKind::Pair => format!("Pair {}", upcard_label(total)),
+        _ => format!("{} {}", kind, total),
+    }
+}
+
+// First decision taken with the starting hand; None when the round ended without one (naturals)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActionCounts {
+    pub hit: u32,
+    pub stand: u32,
+    pub double: u32,
+    pub split: u32,
+    pub none: u32,
+}
+
+impl ActionCounts {
+    fn add(&mut self, action: Option<PlayerAction>) {
+        match action {
+            Some(PlayerAction::Hit) => self.hit += 1,
+            Some(PlayerAction::Stand) => self.stand += 1,
+            Some(PlayerAction::Double) => self.double += 1,
+            Some(PlayerAction::Split) => self.split += 1,
+            None => self.none += 1,
+        }
+    }
+
+    // Chart letter of the most common first action
+    pub fn dominant_letter(&self) -> &'static str {
+        [(self.hit, "H"), (self.stand, "S"), (self.double, "D"), (self.split, "P"), (self.none, "-")]
+            .into_iter()
+            .max_by_key(|&(count, _)| count)
+            .map(|(_, letter)| letter)
+            .unwrap_or("-")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvCellStats {
+    pub rounds: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub net_units: f64, // Net result in initial bets, summed over all hands played from this start
+    pub actions: ActionCounts,
+}
+
+impl EvCellStats {
+    pub fn ev_per_hand(&self) -> f64 {
+        if self.rounds > 0 { self.net_units / self.rounds as f64 } else { 0.0 }
+    }
+}
+
+// Results of every round grouped by chart cell, to see which cells win or cost the most
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvMatrix {
+    #[serde(with = "cell_entries")]
+    pub cells: BTreeMap<ChartCell, EvCellStats>,
+}
+
+// JSON object keys must be strings, so the cells are saved as a list of entries
+mod cell_entries {
+    use super::{ChartCell, EvCellStats};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    type Cells = BTreeMap<ChartCell, EvCellStats>;
+
+    pub fn serialize<S: Serializer>(cells: &Cells, serializer: S) -> Result<S::Ok, S::Error> {
+        cells.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cells, D::Error> {
+        Ok(Vec::<(ChartCell, EvCellStats)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+impl EvMatrix {
+    // Records one finished round from "Your" hands (after splits) and the dealer upcard
+    pub fn record_round(&mut self, your_hands: &[Hand], dealer_upcard: Card) {
+        let Some(first_hand) = your_hands.first() else { return; };
+        let starting_cards: Vec<Card> = first_hand.history.iter()
+            .filter_map(|e| match e { HandEvent::Dealt(card) => Some(*card), _ => None })
+            .take(2)
+            .collect();
+        if starting_cards.len() < 2 {
+            return;
+        }
+        let first_action = first_hand.history.iter().find_map(|e| match e {
+            HandEvent::Hit(_) => Some(PlayerAction::Hit),
+            HandEvent::Stand(_) => Some(PlayerAction::Stand),
+            HandEvent::Double(_) => Some(PlayerAction::Double),
+            HandEvent::Split(_) => Some(PlayerAction::Split),
+            _ => None,
+        });
+        // Split hands each carry the initial bet; a doubled hand carries twice that
+        let initial_bet = if first_hand.was_doubled() { first_hand.bet.as_f64() / 2.0 } else { first_hand.bet.as_f64() };
+        if initial_bet <= 0.0 {
+            return;
+        }
+        let net = your_hands.iter().fold(Money::ZERO, |total, h| total + h.net_result);
+
+        let cell = self.cells.entry(ChartCell::of(&starting_cards, dealer_upcard)).or_default();
+        cell.rounds += 1;
+        if net > Money::ZERO { cell.wins += 1; } else if net < Money::ZERO { cell.losses += 1; } else { cell.pushes += 1; }
+        cell.net_units += net.as_f64() / initial_bet;
+        cell.actions.add(first_action);
+    }
+
+    pub fn total_rounds(&self) -> u32 {
+        self.cells.values().map(|c| c.rounds).sum()
+    }
+
+    // Chart rows present in the matrix, in chart order: hard, soft, then pairs
+    pub fn rows(&self) -> Vec<(HandKind, u8)> {
+        let mut rows: Vec<(HandKind, u8)> = self.cells.keys().map(|c| (c.kind, c.total)).collect();
+        rows.dedup();
+        rows
+    }
+
+    // Matrix in the strategy chart layout: EV per hand in % of the initial bet plus the most common
+    // first action, e.g. "-12 H"; "." where the cell never came up
+    pub fn to_log_lines(&self) -> Vec<String> {
+        let upcards: Vec<u8> = (2..=11).collect();
+        let mut lines = vec![
+            format!("--- EV by Starting Hand vs Dealer Upcard ({} rounds; EV % of initial bet, most common action) ---", self.total_rounds()),
+            format!("{:<9}{}", "", upcards.iter().map(|&u| format!("{:>8}", upcard_label(u))).collect::<String>()),
+        ];
+        for (kind, total) in self.rows() {
+            let row: String = upcards.iter().map(|&upcard| {
+                match self.cells.get(&ChartCell { kind, total, upcard }) {
+                    Some(cell) => format!("{:>8}", format!("{:+.0} {}", cell.ev_per_hand() * 100.0, cell.actions.dominant_letter())),
+                    None => format!("{:>8}", "."),
+                }
+            }).collect();
+            lines.push(format!("{:<9}{}", row_label(kind, total), row));
+        }
+
+        // Cells seen often enough to judge, by total units lost
+        let mut costliest: Vec<(&ChartCell, &EvCellStats)> = self.cells.iter()
+            .filter(|(_, c)| c.rounds >= config::EV_MATRIX_MIN_ROUNDS && c.net_units < 0.0)
+            .collect();
+        costliest.sort_by(|a, b| a.1.net_units.total_cmp(&b.1.net_units));
+        for (cell, stats) in costliest.iter().take(config::EV_MATRIX_COSTLIEST_CELLS) {
+            lines.push(format!("Costly Cell: {} vs {}: {:+.1} bets over {} rounds (EV {:+.1}%, W/L/P {}/{}/{})",
+                cell.row_label(), upcard_label(cell.upcard), stats.net_units, stats.rounds,
+                stats.ev_per_hand() * 100.0, stats.wins, stats.losses, stats.pushes));
+        }
+        lines
+    }
+}
+
+pub fn ev_matrix_path(run_id: u64) -> PathBuf {
+    PathBuf::from(&config::settings().output_dir).join(format!("{}{}", run_id, config::EV_MATRIX_SUFFIX))
+}
+
+// One CSV row per cell, for spreadsheets and plotting
+pub fn write_ev_matrix_csv(matrix: &EvMatrix, run_id: u64) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut text = String::from("kind,total,row,upcard,rounds,wins,losses,pushes,net_units,ev_per_hand_pct,hit,stand,double,split,no_decision\n");
+    for (cell, stats) in &matrix.cells {
+        let a = &stats.actions;
+        text.push_str(&format!("{},{},{},{},{},{},{},{},{:.4},{:.4},{},{},{},{},{}\n",
+            cell.kind, cell.total, cell.row_label(), upcard_label(cell.upcard), stats.rounds, stats.wins, stats.losses,
+            stats.pushes, stats.net_units, stats.ev_per_hand() * 100.0, a.hit, a.stand, a.double, a.split, a.none));
+    }
+    let path = ev_matrix_path(run_id);
+    fs::create_dir_all(&config::settings().output_dir)?;
+    fs::write(&path, text)?;
+    Ok(path)
+}
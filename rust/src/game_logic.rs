@@ -1,46 +1,104 @@
 // src/game_logic.rs
 use crate::card_deck::{Deck, Card, Rank};
-use crate::hand::{Hand, HandStatus};
+use crate::hand::{Hand, HandStatus, HandEvent, DecisionSource};
 use crate::player::{Player, Dealer};
-use crate::strategy::{get_basic_strategy_action, PlayerAction};
+use crate::strategy::{get_basic_strategy_action, get_ai_profile_action, restrict_to_allowed_action, AiProfile, PlayerAction};
 use crate::config;
+use crate::money::{Money, smallest_chip};
 use crate::utils;
 use crate::stats::SessionStats;
+use crate::trainer::{estimate_ev_cost, TrainerMistake};
+
+// Everything "Your" next decision can change, captured so practice mode can take it back.
+// The deck is included so an undone hit draws the same card again.
+#[derive(Clone)]
+struct DecisionSnapshot {
+    deck: Deck,
+    player: Player,
+    session_stats: SessionStats,
+    hand_idx: usize,
+    auto_play_book: bool,
+}
+
+impl DecisionSnapshot {
+    fn capture(deck: &Deck, player: &Player, session_stats: &SessionStats, hand_idx: usize, auto_play_book: bool) -> Self {
+        DecisionSnapshot {
+            deck: deck.clone(),
+            player: player.clone(),
+            session_stats: session_stats.clone(),
+            hand_idx,
+            auto_play_book,
+        }
+    }
+
+    fn restore(self, deck: &mut Deck, player: &mut Player, session_stats: &mut SessionStats,
+               hand_idx: &mut usize, auto_play_book: &mut bool) {
+        // Undo bookkeeping and trainer grades are never rolled back: a graded mistake stays
+        // a mistake even when it is taken back, or undo would inflate trainer accuracy
+        let (undos_used, redos_used) = (session_stats.undos_used, session_stats.redos_used);
+        let trainer_stats = session_stats.trainer_stats.take();
+        *deck = self.deck;
+        *player = self.player;
+        *session_stats = self.session_stats;
+        session_stats.undos_used = undos_used;
+        session_stats.redos_used = redos_used;
+        session_stats.trainer_stats = trainer_stats;
+        *hand_idx = self.hand_idx;
+        *auto_play_book = self.auto_play_book;
+    }
+}
+
+// Saves the current state onto `to_stack` and restores the latest snapshot from `from_stack`
+// (undo: undo -> redo, redo: redo -> undo). Returns false if there was nothing to restore.
+fn step_decision_history(
+    from_stack: &mut Vec<DecisionSnapshot>,
+    to_stack: &mut Vec<DecisionSnapshot>,
+    deck: &mut Deck,
+    player: &mut Player,
+    session_stats: &mut SessionStats,
+    hand_idx: &mut usize,
+    auto_play_book: &mut bool,
+) -> bool {
+    let Some(snapshot) = from_stack.pop() else { return false; };
+    to_stack.push(DecisionSnapshot::capture(deck, player, session_stats, *hand_idx, *auto_play_book));
+    snapshot.restore(deck, player, session_stats, hand_idx, auto_play_book);
+    true
+}
 
 pub fn play_blackjack_round(
     deck: &mut Deck,
-    all_players_at_table: &mut Vec<Player>,
+    all_players_at_table: &mut [Player],
     dealer: &mut Dealer,
-    your_player_balance: &mut f64,
+    your_player_balance: &mut Money,
     session_stats: &mut SessionStats,
     is_simulation_round: bool,
     run_timestamp: u64,
-    _delay_multiplier: f64,
 ) -> bool {
 
-    if let Some(user_player) = all_players_at_table.get_mut(0) {
-        if user_player.is_user {
-            user_player.reset_round_flags();
+    // "You" can sit at any seat; players are stored in dealing order (first base first)
+    let your_idx = match all_players_at_table.iter().position(|p| p.is_user) {
+        Some(idx) => idx,
+        None => {
+            log::error!("RUN_ID:{} - User player not found at table.", run_timestamp);
+            return false;
         }
-    } else {
-        log::error!("RUN_ID:{} - User player (index 0) not found.", run_timestamp);
-        return false;
-    }
+    };
+    all_players_at_table[your_idx].reset_round_flags();
 
-    let your_initial_bet: f64;
-    if let Some(user_player) = all_players_at_table.get_mut(0) {
+    let your_initial_bet: Money;
+    if let Some(user_player) = all_players_at_table.get_mut(your_idx) {
         if user_player.is_user {
             if is_simulation_round {
-                if *your_player_balance >= config::DEFAULT_BET {
-                    your_initial_bet = config::DEFAULT_BET;
+                if *your_player_balance >= config::settings().default_bet {
+                    your_initial_bet = config::settings().default_bet;
                     user_player.hands[0].bet = your_initial_bet;
-                    println!("Simulation ('You'): Auto-betting default ${:.2}", your_initial_bet);
+                    println!("Simulation ('You'): Auto-betting default ${}", your_initial_bet);
                 } else {
-                    println!("Simulation ('You'): Balance (${:.2}) too low for default bet (${:.2}).", *your_player_balance, config::DEFAULT_BET);
+                    println!("Simulation ('You'): Balance (${}) too low for default bet (${}).", *your_player_balance, config::settings().default_bet);
                     return false;
                 }
             } else {
-                match utils::get_your_bet_from_input(*your_player_balance, config::DEFAULT_BET, config::MIN_BET_ALLOWED) {
+                match utils::get_your_bet_from_input(*your_player_balance, config::settings().default_bet, config::MIN_BET_ALLOWED, smallest_chip(config::CHIP_DENOMINATIONS)) {
                     Some(bet) => {
                         your_initial_bet = bet;
                         user_player.hands[0].bet = your_initial_bet;
@@ -49,7 +107,7 @@ pub fn play_blackjack_round(
                 }
             }
             session_stats.hands_played_session += 1;
-        } else { your_initial_bet = 0.0; }
+        } else { your_initial_bet = Money::ZERO; }
     } else { return false; }
 
     dealer.hand.cards.clear();
@@ -60,11 +118,12 @@ pub fn play_blackjack_round(
             hand.cards.clear();
             hand.status = HandStatus::Active;
             hand.is_split_ace = false;
-            hand.actions_taken.clear();
+            hand.history.clear();
+            hand.net_result = Money::ZERO;
             if player.is_user {
                 hand.bet = your_initial_bet;
             } else {
-                hand.bet = 0.0;
+                hand.bet = Money::ZERO;
             }
         } else {
             log::error!("RUN_ID:{} - Player {} has no hands to reset.", run_timestamp, player.name);
@@ -86,23 +145,29 @@ pub fn play_blackjack_round(
     if let Some(card) = deck.deal() { dealer.hand.add_card(card); }
     else { log::error!("RUN_ID:{} - Deck empty during initial deal (dealer hole card)!", run_timestamp); return false; }
 
-    if let Some(user_player) = all_players_at_table.get(0) {
-        if user_player.is_user {
-            utils::display_your_hands_and_dealer(user_player, dealer, true);
-        }
+    utils::display_your_hands_and_dealer(&all_players_at_table[your_idx], dealer, true);
+    for ai_p in all_players_at_table.iter().filter(|p| !p.is_user) {
+        let hand_val = ai_p.hands[0].value();
+        let cards_str: String = ai_p.hands[0].cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(", ");
+        println!("{} (AI, Seat {}): {} (Value: {})", ai_p.name, ai_p.seat + 1, cards_str, hand_val);
     }
-    for i in 1..config::NUM_PLAYERS {
-        if let Some(ai_p) = all_players_at_table.get(i) {
-            let hand_val = ai_p.hands[0].value();
-            let cards_str: String = ai_p.hands[0].cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(", ");
-            println!("{} (AI): {} (Value: {})", ai_p.name, cards_str, hand_val);
+    if dealer.hand.cards[0].rank == Rank::Ace {
+        // Only AI seats are offered insurance here. Their bets are not tracked in money, so
+        // insurance is settled in insurance bets once the hole card is shown.
+        for ai_p in all_players_at_table.iter_mut().filter(|p| !p.is_user) {
+            let taken = ai_p.ai_profile.takes_insurance();
+            ai_p.hands[0].record(HandEvent::Insurance { taken });
+            if taken {
+                println!("{} (AI) takes insurance.", ai_p.name);
+                session_stats.ai_insurance_taken += 1;
+            }
         }
     }
     utils::sleep_ms(utils::get_delay_multiplied(500, is_simulation_round));
 
     let mut your_bj_resolved_this_round = false;
-    if let Some(user_player) = all_players_at_table.get_mut(0) {
-        if user_player.is_user && user_player.hands[0].is_natural_blackjack() {
+    if let Some(user_player) = all_players_at_table.get_mut(your_idx) {
+        if user_player.hands[0].is_natural_blackjack() {
             user_player.hands[0].status = HandStatus::Blackjack;
             session_stats.blackjacks_dealt_player += 1;
             println!("\n{} Blackjack!", user_player.name);
@@ -113,45 +178,63 @@ pub fn play_blackjack_round(
                 println!("Dealer also has Blackjack! It's a Push for Your hand.");
                 session_stats.total_pushes += 1;
             } else {
-                let winnings = user_player.hands[0].bet * config::BLACKJACK_PAYOUT_MULTIPLIER;
+                let winnings = user_player.hands[0].bet.ratio_payout(
+                    config::settings().blackjack_payout_numerator, config::settings().blackjack_payout_denominator, smallest_chip(config::CHIP_DENOMINATIONS),
+                );
                 *your_player_balance += winnings;
+                user_player.hands[0].net_result = winnings;
                 session_stats.total_wins +=1;
-                println!("You win ${:.2}! (Payout: {}/{})", winnings, config::BLACKJACK_PAYOUT_NUMERATOR, config::BLACKJACK_PAYOUT_DENOMINATOR);
+                println!("You win ${}! (Payout: {}/{})", winnings, config::settings().blackjack_payout_numerator, config::settings().blackjack_payout_denominator);
             }
-            println!("Your balance: ${:.2}", *your_player_balance);
+            println!("Your balance: ${}", *your_player_balance);
             your_bj_resolved_this_round = true;
         }
     }
 
-    for p_idx in 0..all_players_at_table.len() {
-        if all_players_at_table[p_idx].is_user && your_bj_resolved_this_round {
+    for player in all_players_at_table.iter_mut() {
+        if player.is_user && your_bj_resolved_this_round {
             continue;
         }
-        if !all_players_at_table[p_idx].is_user && all_players_at_table[p_idx].hands[0].is_natural_blackjack() {
-            all_players_at_table[p_idx].hands[0].status = HandStatus::Blackjack;
-            let cards_str: String = all_players_at_table[p_idx].hands[0].cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(", ");
-            println!("\n{} (AI) has Blackjack: {}", all_players_at_table[p_idx].name, cards_str);
+        if !player.is_user && player.hands[0].is_natural_blackjack() {
+            player.hands[0].status = HandStatus::Blackjack;
+            let cards_str: String = player.hands[0].cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(", ");
+            println!("\n{} (AI) has Blackjack: {}", player.name, cards_str);
             utils::sleep_ms(utils::get_delay_multiplied(300, is_simulation_round));
             continue;
         }
 
-        println!("\n--- {}'s Turn ---", all_players_at_table[p_idx].name);
-        if all_players_at_table[p_idx].is_user {
+        println!("\n--- {}'s Turn ---", player.name);
+        if player.is_user {
              utils::sleep_ms(utils::get_delay_multiplied(500, is_simulation_round));
         }
 
         let mut current_hand_idx_for_player = 0;
-        let mut auto_play_book_all_your_hands_this_round = if all_players_at_table[p_idx].is_user { is_simulation_round } else { false };
+        let mut auto_play_book_all_your_hands_this_round = if player.is_user { is_simulation_round } else { false };
+        let practice_undo_enabled = player.is_user && session_stats.practice_mode && !is_simulation_round;
+        let mut undo_stack: Vec<DecisionSnapshot> = Vec::new();
+        let mut redo_stack: Vec<DecisionSnapshot> = Vec::new();
 
-        while current_hand_idx_for_player < all_players_at_table[p_idx].hands.len() {
-            let hand_status_check = all_players_at_table[p_idx].hands[current_hand_idx_for_player].status.clone();
+        'hand_loop: loop {
+            if current_hand_idx_for_player >= player.hands.len() {
+                // Last chance to take back the final decision before the turn passes on
+                if practice_undo_enabled && !undo_stack.is_empty() &&
+                   utils::get_user_input("All your hands are done. (U)ndo last action, or press Enter to continue: ") == "u" {
+                    step_decision_history(&mut undo_stack, &mut redo_stack, deck, player, session_stats,
+                                          &mut current_hand_idx_for_player, &mut auto_play_book_all_your_hands_this_round);
+                    session_stats.undos_used += 1;
+                    println!("Undid your last action (practice).");
+                    continue 'hand_loop;
+                }
+                break 'hand_loop;
+            }
+            let hand_status_check = player.hands[current_hand_idx_for_player].status.clone();
             if hand_status_check != HandStatus::Active {
                 current_hand_idx_for_player += 1;
                 continue;
             }
 
             'action_loop: loop {
-                let player_for_display = &all_players_at_table[p_idx];
+                let player_for_display = &*player;
                 let hand_for_display = &player_for_display.hands[current_hand_idx_for_player];
 
                 if hand_for_display.status != HandStatus::Active {
@@ -171,8 +254,8 @@ pub fn play_blackjack_round(
 
                 let player_value_of_current_hand_display = hand_for_display.value();
                 if player_value_of_current_hand_display > 21 {
-                    all_players_at_table[p_idx].hands[current_hand_idx_for_player].status = HandStatus::Busted;
-                    println!("{} Hand {} Busts!", all_players_at_table[p_idx].name, current_hand_idx_for_player + 1);
+                    player.hands[current_hand_idx_for_player].status = HandStatus::Busted;
+                    println!("{} Hand {} Busts!", player.name, current_hand_idx_for_player + 1);
                     break 'action_loop;
                 }
 
@@ -183,25 +266,32 @@ pub fn play_blackjack_round(
                                 (!player_for_display.is_user || *your_player_balance >= hand_for_display.bet);
 
                 let chosen_action: PlayerAction;
-                let is_book_play_for_this_action = (!player_for_display.is_user) ||
-                                                   (player_for_display.is_user && auto_play_book_all_your_hands_this_round) ||
-                                                   (player_for_display.is_user && hand_for_display.actions_taken.contains(&"UserChoseBookMode".to_string()));
+                let decision_source: DecisionSource;
+                let is_book_play_for_this_action = !player_for_display.is_user ||
+                                                   auto_play_book_all_your_hands_this_round ||
+                                                   hand_for_display.book_mode_chosen();
 
                 if is_book_play_for_this_action {
-                    let mut book_action = get_basic_strategy_action(
-                        &hand_for_display.cards, dealer_upcard_val, player_for_display.hands.len(), is_simulation_round,
+                    let book_action = if player_for_display.is_user {
+                        get_basic_strategy_action(
+                            &hand_for_display.cards, dealer_upcard_val, player_for_display.hands.len(), is_simulation_round,
+                        )
+                    } else {
+                        get_ai_profile_action(
+                            player_for_display.ai_profile, &hand_for_display.cards, dealer_upcard_val,
+                            player_for_display.hands.len(), can_double, can_split, deck.decision_rng(),
+                        )
+                    };
+                    chosen_action = restrict_to_allowed_action(
+                        book_action, &hand_for_display.cards, dealer_upcard_val, can_double, can_split, is_simulation_round,
                     );
-                    if book_action == PlayerAction::Double && !can_double { book_action = PlayerAction::Hit; }
-                    if book_action == PlayerAction::Split && !can_split {
-                        book_action = get_basic_strategy_action(&hand_for_display.cards, dealer_upcard_val, 10, is_simulation_round);
-                        if book_action == PlayerAction::Split { book_action = PlayerAction::Hit; }
-                    }
-                    chosen_action = book_action;
-                    let action_desc = match chosen_action {
-                        PlayerAction::Hit => "Hit", PlayerAction::Stand => "Stand",
-                        PlayerAction::Double => "Double Down", PlayerAction::Split => "Split",
+                    decision_source = if player_for_display.is_user || player_for_display.ai_profile == AiProfile::Book {
+                        DecisionSource::Book
+                    } else {
+                        DecisionSource::Profile
                     };
-                    println!("{} Hand {} (Book): {}", player_for_display.name, current_hand_idx_for_player + 1, action_desc);
+                    let play_source = if player_for_display.is_user { "Book".to_string() } else { player_for_display.ai_profile.to_string() };
+                    println!("{} Hand {} ({}): {}", player_for_display.name, current_hand_idx_for_player + 1, play_source, chosen_action);
                     utils::sleep_ms(utils::get_delay_multiplied(400, is_simulation_round));
                 } else { // Interactive choice for "You"
                     let mut prompt = format!("Your Hand {}: (H)it, (S)tand", current_hand_idx_for_player + 1);
@@ -211,9 +301,30 @@ pub fn play_blackjack_round(
                     if !auto_play_book_all_your_hands_this_round {
                          prompt.push_str(", (B)ook plays all your hands"); valid_choices.push("b");
                     }
+                    if practice_undo_enabled && !undo_stack.is_empty() { prompt.push_str(", (U)ndo"); }
+                    if practice_undo_enabled && !redo_stack.is_empty() { prompt.push_str(", (R)edo"); }
                     prompt.push_str(": ");
                     let user_input = utils::get_user_input(&prompt);
 
+                    if practice_undo_enabled && (user_input == "u" || user_input == "r") {
+                        let is_undo = user_input == "u";
+                        let (from_stack, to_stack) = if is_undo { (&mut undo_stack, &mut redo_stack) }
+                                                     else { (&mut redo_stack, &mut undo_stack) };
+                        if step_decision_history(from_stack, to_stack, deck, player, session_stats,
+                                                 &mut current_hand_idx_for_player, &mut auto_play_book_all_your_hands_this_round) {
+                            if is_undo { session_stats.undos_used += 1; } else { session_stats.redos_used += 1; }
+                            println!("{} your last action (practice).", if is_undo { "Undid" } else { "Redid" });
+                        } else {
+                            println!("Nothing to {}.", if is_undo { "undo" } else { "redo" });
+                        }
+                        continue 'hand_loop;
+                    }
+                    if practice_undo_enabled && valid_choices.contains(&user_input.as_str()) {
+                        undo_stack.push(DecisionSnapshot::capture(deck, player_for_display, session_stats,
+                            current_hand_idx_for_player, auto_play_book_all_your_hands_this_round));
+                        redo_stack.clear();
+                    }
+
                     match user_input.as_str() {
                         "h" => chosen_action = PlayerAction::Hit,
                         "s" => chosen_action = PlayerAction::Stand,
@@ -221,20 +332,49 @@ pub fn play_blackjack_round(
                         "p" if can_split => chosen_action = PlayerAction::Split,
                         "b" if !auto_play_book_all_your_hands_this_round && player_for_display.is_user => {
                             auto_play_book_all_your_hands_this_round = true;
-                            all_players_at_table[p_idx].hands[current_hand_idx_for_player]
-                                .actions_taken.push("UserChoseBookMode".to_string());
+                            player.hands[current_hand_idx_for_player].record(HandEvent::BookModeChosen);
                             println!("Book will play out all your remaining hands for this round.");
                             continue 'action_loop;
                         }
                         _ => { println!("Invalid action or action not allowed."); continue 'action_loop; }
                     }
+                    decision_source = DecisionSource::User;
+
+                    if let Some(trainer) = session_stats.trainer_stats.as_mut() {
+                        let book_play = restrict_to_allowed_action(
+                            get_basic_strategy_action(&hand_for_display.cards, dealer_upcard_val, player_for_display.hands.len(), false),
+                            &hand_for_display.cards, dealer_upcard_val, can_double, can_split, false,
+                        );
+                        let is_correct = book_play == chosen_action;
+                        trainer.record_decision(&hand_for_display.cards, dealer_upcard_val, is_correct);
+                        if is_correct {
+                            println!("Trainer: Correct, Book also says {}.", book_play);
+                        } else {
+                            let dealer_upcard = dealer.hand.cards[0];
+                            // Not clamped: a near-tie can come out slightly negative, within its error
+                            let (ev_cost_bets, ev_cost_std_error) = estimate_ev_cost(&hand_for_display.cards, dealer_upcard,
+                                book_play, chosen_action, config::TRAINER_EV_TRIALS, config::settings().seed.unwrap_or(0));
+                            println!("Trainer: Mistake! Book says {} (you chose {}). Est. cost: {:.3} +/- {:.3} bets (${:.2}).",
+                                book_play, chosen_action, ev_cost_bets, ev_cost_std_error, ev_cost_bets * hand_for_display.bet.as_f64());
+                            trainer.mistakes.push(TrainerMistake {
+                                player_cards: hand_for_display.cards.clone(),
+                                dealer_upcard,
+                                chosen: chosen_action,
+                                book: book_play,
+                                ev_cost_bets,
+                                ev_cost_std_error,
+                                bet: hand_for_display.bet.as_f64(),
+                            });
+                        }
+                    }
                 }
 
-                let current_player_obj_mut_for_action = &mut all_players_at_table[p_idx];
+                let current_player_obj_mut_for_action = &mut *player;
 
                 match chosen_action {
                     PlayerAction::Hit => {
                         let p_hand_mut_for_action = &mut current_player_obj_mut_for_action.hands[current_hand_idx_for_player];
+                        p_hand_mut_for_action.record(HandEvent::Hit(decision_source));
                         if let Some(new_card) = deck.deal() {
                             p_hand_mut_for_action.add_card(new_card);
                             println!("{} Hand {} Hits, draws {}", current_player_obj_mut_for_action.name, current_hand_idx_for_player + 1, new_card);
@@ -252,6 +392,7 @@ pub fn play_blackjack_round(
                     }
                     PlayerAction::Stand => {
                         let p_hand_mut_for_action = &mut current_player_obj_mut_for_action.hands[current_hand_idx_for_player];
+                        p_hand_mut_for_action.record(HandEvent::Stand(decision_source));
                         p_hand_mut_for_action.status = HandStatus::Stood;
                         println!("{} Hand {} Stands.", current_player_obj_mut_for_action.name, current_hand_idx_for_player + 1);
                         break 'action_loop;
@@ -264,13 +405,14 @@ pub fn play_blackjack_round(
                                 session_stats.hands_involved_in_double += 1;
                                 current_player_obj_mut_for_action.hand_involved_in_double_this_round = true;
                             }
-                            p_hand_mut_for_action.bet *= 2.0;
+                            p_hand_mut_for_action.bet = p_hand_mut_for_action.bet * 2;
                         }
+                        p_hand_mut_for_action.record(HandEvent::Double(decision_source));
                         if let Some(new_card) = deck.deal() {
                             p_hand_mut_for_action.add_card(new_card);
                             p_hand_mut_for_action.status = HandStatus::Doubled;
                             let bet_display = if current_player_obj_mut_for_action.is_user {
-                                format!("Bet is now ${:.2}. ", p_hand_mut_for_action.bet)
+                                format!("Bet is now ${}. ", p_hand_mut_for_action.bet)
                             } else { "".to_string() };
                             println!("{} Hand {} Doubles Down. {}Draws {}",
                                 current_player_obj_mut_for_action.name, current_hand_idx_for_player + 1, bet_display, new_card);
@@ -282,18 +424,18 @@ pub fn play_blackjack_round(
                         break 'action_loop;
                     }
                     PlayerAction::Split => {
-                        let original_bet_for_split: f64;
+                        let original_bet_for_split: Money;
                         let is_ace_split_check: bool;
                         let card_to_move_to_new_hand: Card;
                         let mut original_hand_auto_stood = false;
 
                         {
                             let hand_being_split = &mut current_player_obj_mut_for_action.hands[current_hand_idx_for_player];
-                            original_bet_for_split = if current_player_obj_mut_for_action.is_user { hand_being_split.bet } else { 0.0 };
+                            original_bet_for_split = if current_player_obj_mut_for_action.is_user { hand_being_split.bet } else { Money::ZERO };
                             is_ace_split_check = hand_being_split.cards[0].rank == Rank::Ace;
                             card_to_move_to_new_hand = hand_being_split.cards.pop().expect("Hand should have card for split");
                             hand_being_split.is_split_ace = is_ace_split_check;
-                            hand_being_split.actions_taken.push("split_created_new_hand".to_string());
+                            hand_being_split.record(HandEvent::Split(decision_source));
                             if let Some(c1) = deck.deal() { hand_being_split.add_card(c1); }
                             else { log::error!("RUN_ID:{} - Deck empty for 1st split card!", run_timestamp); break 'action_loop; }
                             if hand_being_split.is_split_ace {
@@ -302,10 +444,10 @@ pub fn play_blackjack_round(
                             }
                         }
 
-                        let mut new_player_hand_obj = Hand::new(original_bet_for_split);
-                        new_player_hand_obj.add_card(card_to_move_to_new_hand);
+                        let mut new_player_hand_obj = Hand::new_split_from(
+                            current_hand_idx_for_player + 1, card_to_move_to_new_hand, original_bet_for_split,
+                        );
                         new_player_hand_obj.is_split_ace = is_ace_split_check;
-                        new_player_hand_obj.actions_taken.push(format!("split_from_hand_{}", current_hand_idx_for_player + 1));
                         if let Some(c2) = deck.deal() { new_player_hand_obj.add_card(c2); }
                         else { log::error!("RUN_ID:{} - Deck empty for 2nd split card!", run_timestamp); break 'action_loop; }
                         if new_player_hand_obj.is_split_ace {
@@ -378,21 +520,38 @@ pub fn play_blackjack_round(
         println!("\nAll player hands resolved before dealer's turn. Dealer does not play further.");
     }
 
+    let insured: Vec<&str> = all_players_at_table.iter()
+        .filter(|p| p.hands[0].history.contains(&HandEvent::Insurance { taken: true }))
+        .map(|p| p.name.as_str())
+        .collect();
+    if !insured.is_empty() {
+        let dealer_had_blackjack = dealer.hand.is_natural_blackjack(); // The dealer never draws to a natural
+        for name in insured {
+            if dealer_had_blackjack {
+                println!("{} (AI) insurance wins, paid 2:1.", name);
+                session_stats.ai_insurance_won += 1;
+            } else {
+                println!("{} (AI) insurance loses.", name);
+            }
+        }
+    }
+
     println!("\n--- Results for Your Hands ---");
     let dealer_final_value_for_comparison = if dealer.hand.status == HandStatus::Busted { 0 } else { dealer.hand.value() };
 
-    if let Some(user_player) = all_players_at_table.get(0) {
+    if let Some(user_player) = all_players_at_table.get_mut(your_idx) {
         if user_player.is_user {
-            for (i, p_hand) in user_player.hands.iter().enumerate() {
+            let num_your_hands = user_player.hands.len();
+            for (i, p_hand) in user_player.hands.iter_mut().enumerate() {
                 if p_hand.status == HandStatus::Blackjack && your_bj_resolved_this_round {
-                    println!("Your Hand {}: Blackjack win (already paid). Bet: ${:.2}", i + 1, p_hand.bet);
+                    println!("Your Hand {}: Blackjack win (already paid). Bet: ${}", i + 1, p_hand.bet);
                     continue;
                 }
                 let player_final_val = p_hand.value();
-                let mut result_str = format!("Your Hand {} (${:.2}): ", i + 1, p_hand.bet);
-                let mut net_change_for_this_hand = 0.0;
-                let is_part_of_split_scenario = user_player.hands.len() > 1 || p_hand.actions_taken.iter().any(|a| a.starts_with("split"));
-                let is_doubled_this_hand = p_hand.status == HandStatus::Doubled;
+                let mut result_str = format!("Your Hand {} (${}): ", i + 1, p_hand.bet);
+                let mut net_change_for_this_hand = Money::ZERO;
+                let is_part_of_split_scenario = num_your_hands > 1 || p_hand.was_split();
+                let is_doubled_this_hand = p_hand.was_doubled();
 
                 match p_hand.status {
                     HandStatus::Busted => {
@@ -416,12 +575,13 @@ pub fn play_blackjack_round(
                             session_stats.total_losses += 1;
                         } else {
                             result_str.push_str(&format!("Push ({} vs {}).", player_final_val, dealer_final_value_for_comparison));
-                            net_change_for_this_hand = 0.0;
+                            net_change_for_this_hand = Money::ZERO;
                             session_stats.total_pushes += 1;
                         }
                     }
                 }
                 println!("{}", result_str);
+                p_hand.net_result = net_change_for_this_hand;
                 if is_part_of_split_scenario {
                     session_stats.earnings_from_split_hands += net_change_for_this_hand;
                     session_stats.num_resolved_split_hands += 1;
@@ -436,7 +596,7 @@ pub fn play_blackjack_round(
             }
         }
     }
-    println!("Your balance after round: ${:.2}", *your_player_balance);
+    println!("Your balance after round: ${}", *your_player_balance);
     utils::sleep_ms(utils::get_delay_multiplied(500, is_simulation_round));
     true
 }
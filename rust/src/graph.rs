@@ -1,9 +1,20 @@
 // src/graph.rs
-use crate::config::{LOGS_DIR_NAME, GRAPH_EXTENSION};
+use crate::config;
+use crate::drill::{all_drill_cells, first_cards_for_cell};
+use crate::ev_matrix::{row_label, ChartCell, EvMatrix};
+use crate::hand::HandEvent;
+use crate::money::Money;
+use crate::player::Player;
+use crate::strategy::{get_basic_strategy_action, PlayerAction};
+use crate::trainer::{upcard_label, HandKind};
+use plotters::element::DashedPathElement;
 use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
 use plotters_bitmap::bitmap_pixel::RGBPixel;
 use plotters_bitmap::BitMapBackend;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 // Import specific style elements
 use plotters::style::{RED, BLUE, WHITE, BLACK, Color};
@@ -11,24 +22,144 @@ use plotters::style::{RED, BLUE, WHITE, BLACK, Color};
 
 pub const MATPLOTLIB_AVAILABLE: bool = true;
 
+// Draws `$body` on a drawing area named `$root`, backed by an SVG file when the path ends in
+// ".svg" and a bitmap otherwise. The body is expanded once per backend.
+macro_rules! draw_to_file {
+    ($path:expr, $size:expr, |$root:ident| $body:block) => {{
+        let path: &Path = $path;
+        create_graph_dir(path)?;
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) {
+            let $root = SVGBackend::new(path, $size).into_drawing_area();
+            $root.fill(&WHITE)?;
+            $body
+            $root.present()?;
+        } else {
+            let $root = BitMapBackend::<RGBPixel>::new(path, $size).into_drawing_area();
+            $root.fill(&WHITE)?;
+            $body
+            $root.present()?;
+        }
+    }};
+}
+
+// Every graph goes through draw_to_file!, which calls this first: a fresh --output-dir
+// (or the directory of a comparison's --out) does not exist yet
+fn create_graph_dir(path: &Path) -> std::io::Result<()> {
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => std::fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+}
+
+// Graphs of a run are written to the output directory as <RUN_ID>_<name>.<png|svg>
+fn run_graph_path(run_timestamp: u64, name: &str) -> PathBuf {
+    PathBuf::from(&config::settings().output_dir)
+        .join(format!("{}_{}.{}", run_timestamp, name, config::settings().graph_format.extension()))
+}
+
+// Notable things about one of "Your" rounds, marked on the balance graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BalanceEvent {
+    Blackjack,
+    Split,
+    Double,
+    Reshuffle, // The shoe was reshuffled before the round
+    BigWin,    // Net result of at least REPLAY_LARGE_SWING_BETS of the round's initial bet
+    BigLoss,
+}
+
+impl BalanceEvent {
+    const ALL: [BalanceEvent; 6] = [
+        BalanceEvent::Reshuffle, BalanceEvent::BigWin, BalanceEvent::BigLoss,
+        BalanceEvent::Blackjack, BalanceEvent::Split, BalanceEvent::Double,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            BalanceEvent::Blackjack => "Blackjack",
+            BalanceEvent::Split => "Split",
+            BalanceEvent::Double => "Double",
+            BalanceEvent::Reshuffle => "Reshuffle",
+            BalanceEvent::BigWin => "Big Win",
+            BalanceEvent::BigLoss => "Big Loss",
+        }
+    }
+
+    fn color(self) -> RGBColor {
+        match self {
+            BalanceEvent::Blackjack => RGBColor(218, 165, 32),
+            BalanceEvent::Split => RGBColor(0, 150, 150),
+            BalanceEvent::Double => RGBColor(140, 60, 170),
+            BalanceEvent::Reshuffle => RGBColor(150, 150, 150),
+            BalanceEvent::BigWin => RGBColor(30, 150, 60),
+            BalanceEvent::BigLoss => RGBColor(200, 30, 30),
+        }
+    }
+
+    // Outline of the marker, in pixels around the point
+    fn marker_shape(self) -> Vec<(i32, i32)> {
+        match self {
+            BalanceEvent::BigWin => vec![(-6, 5), (6, 5), (0, -7)],
+            BalanceEvent::BigLoss => vec![(-6, -5), (6, -5), (0, 7)],
+            BalanceEvent::Blackjack => (0..12).map(|i| {
+                let angle = i as f64 * std::f64::consts::PI / 6.0;
+                ((5.0 * angle.cos()).round() as i32, (5.0 * angle.sin()).round() as i32)
+            }).collect(),
+            BalanceEvent::Split => vec![(0, -6), (6, 0), (0, 6), (-6, 0)],
+            BalanceEvent::Double => vec![(-4, -4), (4, -4), (4, 4), (-4, 4)],
+            BalanceEvent::Reshuffle => vec![(-1, -7), (1, -7), (1, 7), (-1, 7)],
+        }
+    }
+
+    // Events of a finished round, from "Your" hands and net result
+    pub fn of_round(players: &[Player], reshuffled: bool, your_net_result: Money) -> Vec<BalanceEvent> {
+        let mut events = Vec::new();
+        if reshuffled {
+            events.push(BalanceEvent::Reshuffle);
+        }
+        let mut big_swing = config::settings().default_bet * config::REPLAY_LARGE_SWING_BETS;
+        if let Some(you) = players.iter().find(|p| p.is_user) {
+            if let Some(first_hand) = you.hands.first() {
+                big_swing = first_hand.original_bet() * config::REPLAY_LARGE_SWING_BETS;
+            }
+            if you.hands.len() == 1 && you.hands[0].is_natural_blackjack() {
+                events.push(BalanceEvent::Blackjack);
+            }
+            let any_event = |wanted: fn(&HandEvent) -> bool| you.hands.iter().any(|h| h.history.iter().any(wanted));
+            if any_event(|e| matches!(e, HandEvent::Split(_))) {
+                events.push(BalanceEvent::Split);
+            }
+            if any_event(|e| matches!(e, HandEvent::Double(_))) {
+                events.push(BalanceEvent::Double);
+            }
+        }
+        if your_net_result >= big_swing {
+            events.push(BalanceEvent::BigWin);
+        } else if your_net_result <= -big_swing {
+            events.push(BalanceEvent::BigLoss);
+        }
+        events
+    }
+}
+
+// `events` are (index into balance_history, event), drawn as markers on the line; pass none for
+// long simulations, where they would cover it
 pub fn generate_balance_graph(
     balance_history: &[f64],
+    events: &[(usize, BalanceEvent)],
+    mode: &str,
     run_timestamp: u64,
     starting_balance: f64,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
     if balance_history.len() < 2 {
         log::info!("Not enough data points to generate balance graph (need at least 2).");
-        return Ok(());
+        return Ok(None);
     }
 
-    let logs_dir = PathBuf::from(LOGS_DIR_NAME);
-    let graph_filename = format!("{}.{}", run_timestamp, GRAPH_EXTENSION);
+    let logs_dir = PathBuf::from(&config::settings().output_dir);
+    let graph_filename = format!("{}.{}", run_timestamp, config::settings().graph_format.extension());
     let graph_file_path = logs_dir.join(&graph_filename);
 
-    let root_area = BitMapBackend::<RGBPixel>::new(&graph_file_path, (1200, 600))
-        .into_drawing_area();
-    root_area.fill(&WHITE)?;
-
     let min_balance_val = balance_history.iter().cloned().fold(f64::INFINITY, f64::min);
     let max_balance_val = balance_history.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
     
@@ -39,47 +170,412 @@ pub fn generate_balance_graph(
     let y_min_final = if y_min_plot >= y_max_plot { y_max_plot - y_range_height.max(1.0) } else { y_min_plot };
     let y_max_final = if y_max_plot <= y_min_final { y_min_final + y_range_height.max(1.0) } else { y_max_plot };
 
-    let mut chart = ChartBuilder::on(&root_area)
-        .caption(format!("Player Balance Over Simulation (Run ID: {})", run_timestamp), ("sans-serif", 30).into_font())
-        .margin(10)
-        .x_label_area_size(40)
-        .y_label_area_size(60)
-        .build_cartesian_2d(0..balance_history.len() -1 , y_min_final..y_max_final)?;
-
-    chart.configure_mesh()
-        .x_desc("Hand Number (0 = Initial State)")
-        .y_desc("Player Balance ($)")
-        .draw()?;
-
-    // Plot balance history
-    chart.draw_series(LineSeries::new(
-        balance_history.iter().enumerate().map(|(i, &bal)| (i, bal)),
-        BLUE.mix(0.8).stroke_width(2), 
-    ))?
-    .label("Balance")
-    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.mix(0.8).filled()));
-
-    // --- Fallback: Solid Red Line for Starting Balance ---
-    let starting_balance_line_style = RED.mix(0.8).stroke_width(2); // Solid red line
-
-    chart.draw_series(LineSeries::new(
-        vec![(0, starting_balance), (balance_history.len() -1 , starting_balance)],
-        starting_balance_line_style,
-    ))?
-    .label(format!("Starting Balance (${:.2})", starting_balance))
-    .legend(|(x, y)| { 
-        PathElement::new(vec![(x, y), (x + 20, y)], RED.mix(0.8).filled())
-    });
-    // --- END Fallback ---
+    draw_to_file!(&graph_file_path, (1200, 600), |root_area| {
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(format!("Player Balance: {} (Run ID: {})", mode, run_timestamp), ("sans-serif", 26).into_font())
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0..balance_history.len() -1 , y_min_final..y_max_final)?;
+
+        chart.configure_mesh()
+            .x_desc("Hand Number (0 = Initial State)")
+            .y_desc("Player Balance ($)")
+            .draw()?;
 
-    chart.configure_series_labels()
-        .background_style(WHITE.mix(0.8))
-        .border_style(BLACK)
-        .draw()?;
+        // Plot balance history
+        chart.draw_series(LineSeries::new(
+            balance_history.iter().enumerate().map(|(i, &bal)| (i, bal)),
+            BLUE.mix(0.8).stroke_width(2), 
+        ))?
+        .label("Balance")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.mix(0.8).filled()));
 
-    root_area.present()?;
+        // --- Fallback: Solid Red Line for Starting Balance ---
+        let starting_balance_line_style = RED.mix(0.8).stroke_width(2); // Solid red line
+
+        chart.draw_series(LineSeries::new(
+            vec![(0, starting_balance), (balance_history.len() -1 , starting_balance)],
+            starting_balance_line_style,
+        ))?
+        .label(format!("Starting Balance (${:.2})", starting_balance))
+        .legend(|(x, y)| { 
+            PathElement::new(vec![(x, y), (x + 20, y)], RED.mix(0.8).filled())
+        });
+        // --- END Fallback ---
+
+        for kind in BalanceEvent::ALL {
+            let points: Vec<(usize, f64)> = events.iter()
+                .filter(|&&(_, event)| event == kind)
+                .filter_map(|&(i, _)| balance_history.get(i).map(|&bal| (i, bal)))
+                .collect();
+            if points.is_empty() {
+                continue;
+            }
+            let legend = format!("{} ({})", kind.label(), points.len());
+            let drawn = if kind == BalanceEvent::Reshuffle {
+                let color = kind.color();
+                chart.draw_series(points.iter().map(|&(i, _)| {
+                    DashedPathElement::new(vec![(i, y_min_final), (i, y_max_final)], 6, 4, color.stroke_width(2))
+                }))?
+            } else {
+                // Win/loss triangles sit above/below the line, clear of the round's other markers
+                let lift = match kind { BalanceEvent::BigWin => -12, BalanceEvent::BigLoss => 12, _ => 0 };
+                let shape: Vec<(i32, i32)> = kind.marker_shape().into_iter().map(|(x, y)| (x, y + lift)).collect();
+                chart.draw_series(points.iter().map(|&p| EmptyElement::at(p) + Polygon::new(shape.clone(), kind.color().filled())))?
+            };
+            drawn.label(legend).legend(move |(x, y)| EmptyElement::at((x + 10, y)) + Polygon::new(kind.marker_shape(), kind.color().filled()));
+        }
+
+        chart.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    });
     log::info!("Balance graph saved to: {:?}", graph_file_path);
     println!("\nBalance graph saved to: {:?}", graph_file_path);
 
+    Ok(Some(graph_file_path))
+}
+
+// One colored, labelled cell of a heatmap; row 0 is drawn at the top
+struct HeatmapCell {
+    row: usize,
+    column: usize,
+    color: RGBColor,
+    label: String,
+}
+
+// Grid in the strategy chart layout: chart rows down the side, dealer upcards 2-A across the top
+fn draw_heatmap(path: &Path, title: &str, row_labels: &[String], cells: &[HeatmapCell]) -> Result<(), Box<dyn std::error::Error>> {
+    let num_rows = row_labels.len();
+    let height = (num_rows as u32 * 26 + 120).max(300);
+    let column_labels: Vec<String> = (2..=11u8).map(upcard_label).collect();
+    draw_to_file!(path, (900, height), |root_area| {
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 24).into_font())
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(80)
+            // Segmented ranges include their end value, hence the "- 1"
+            .build_cartesian_2d((0..column_labels.len() - 1).into_segmented(), (0..num_rows - 1).into_segmented())?;
+
+        chart.configure_mesh()
+            .disable_mesh()
+            .x_labels(column_labels.len())
+            .y_labels(num_rows)
+            .x_label_formatter(&|x| match x {
+                SegmentValue::CenterOf(i) => column_labels.get(*i).cloned().unwrap_or_default(),
+                _ => String::new(),
+            })
+            // Rows count up from the bottom in chart coordinates, so flip them to read top-down
+            .y_label_formatter(&|y| match y {
+                SegmentValue::CenterOf(i) if *i < num_rows => row_labels[num_rows - 1 - *i].clone(),
+                _ => String::new(),
+            })
+            .x_desc("Dealer Upcard")
+            .draw()?;
+
+        let label_style = TextStyle::from(("sans-serif", 14).into_font()).pos(Pos::new(HPos::Center, VPos::Center));
+        for cell in cells {
+            let y = num_rows - 1 - cell.row;
+            chart.draw_series(std::iter::once(Rectangle::new(
+                [(SegmentValue::Exact(cell.column), SegmentValue::Exact(y)), (SegmentValue::Exact(cell.column + 1), SegmentValue::Exact(y + 1))],
+                cell.color.filled(),
+            )))?;
+            chart.draw_series(std::iter::once(Text::new(
+                cell.label.clone(), (SegmentValue::CenterOf(cell.column), SegmentValue::CenterOf(y)), label_style.clone(),
+            )))?;
+        }
+    });
+    Ok(())
+}
+
+fn action_color(action: PlayerAction) -> RGBColor {
+    match action {
+        PlayerAction::Hit => RGBColor(244, 143, 143),
+        PlayerAction::Stand => RGBColor(246, 226, 122),
+        PlayerAction::Double => RGBColor(128, 180, 240),
+        PlayerAction::Split => RGBColor(150, 215, 140),
+    }
+}
+
+fn action_letter(action: PlayerAction) -> &'static str {
+    match action {
+        PlayerAction::Hit => "H",
+        PlayerAction::Stand => "S",
+        PlayerAction::Double => "D",
+        PlayerAction::Split => "P",
+    }
+}
+
+// Red for losing cells, green for winning ones, saturating at +/- one bet per hand
+pub fn ev_color(ev_per_hand: f64) -> RGBColor {
+    let strength = ev_per_hand.abs().min(1.0);
+    let fade = |full: u8| (255.0 - (255.0 - full as f64) * strength) as u8;
+    if ev_per_hand < 0.0 {
+        RGBColor(fade(215), fade(48), fade(39))
+    } else {
+        RGBColor(fade(26), fade(152), fade(80))
+    }
+}
+
+// The Book's play for every two-card starting hand, colored by action
+pub fn generate_strategy_heatmap(run_timestamp: u64) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut row_keys: Vec<(HandKind, u8)> = all_drill_cells().into_iter().map(|(kind, total, _)| (kind, total)).collect();
+    row_keys.sort();
+    row_keys.dedup();
+
+    let mut cells = Vec::new();
+    for (row, &(kind, total)) in row_keys.iter().enumerate() {
+        for upcard in 2..=11u8 {
+            let (player_cards, _) = first_cards_for_cell((kind, total, upcard));
+            let action = get_basic_strategy_action(&player_cards, upcard, 1, true);
+            cells.push(HeatmapCell { row, column: (upcard - 2) as usize, color: action_color(action), label: action_letter(action).to_string() });
+        }
+    }
+    let row_labels: Vec<String> = row_keys.iter().map(|&(kind, total)| row_label(kind, total)).collect();
+    let path = run_graph_path(run_timestamp, "strategy");
+    draw_heatmap(&path, "Basic Strategy (H = Hit, S = Stand, D = Double, P = Split)", &row_labels, &cells)?;
+    log::info!("Strategy heatmap saved to: {:?}", path);
+    Ok(path)
+}
+
+// EV per hand of each chart cell seen this session, in % of the initial bet
+pub fn generate_ev_heatmap(matrix: &EvMatrix, run_timestamp: u64) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let row_keys = matrix.rows();
+    if row_keys.is_empty() {
+        log::info!("No rounds recorded; skipping EV heatmap.");
+        return Ok(None);
+    }
+    let mut cells = Vec::new();
+    for (row, &(kind, total)) in row_keys.iter().enumerate() {
+        for upcard in 2..=11u8 {
+            let (color, label) = match matrix.cells.get(&ChartCell { kind, total, upcard }) {
+                Some(stats) => (ev_color(stats.ev_per_hand()), format!("{:+.0}", stats.ev_per_hand() * 100.0)),
+                None => (RGBColor(230, 230, 230), String::new()),
+            };
+            cells.push(HeatmapCell { row, column: (upcard - 2) as usize, color, label });
+        }
+    }
+    let row_labels: Vec<String> = row_keys.iter().map(|&(kind, total)| row_label(kind, total)).collect();
+    let path = run_graph_path(run_timestamp, "ev_matrix");
+    let title = format!("EV per Hand, % of Initial Bet ({} rounds, Run ID: {})", matrix.total_rounds(), run_timestamp);
+    draw_heatmap(&path, &title, &row_labels, &cells)?;
+    log::info!("EV heatmap saved to: {:?}", path);
+    Ok(Some(path))
+}
+
+// One run's net result by round, for the comparison graph
+pub struct RunSeries {
+    pub label: String,
+    pub run_id: u64,
+    pub net_by_round: Vec<f64>,
+}
+
+// Mean and 10th/90th percentile of a group's runs at each round (over the runs that got that far)
+fn group_bands(runs: &[&RunSeries]) -> Vec<(usize, f64, f64, f64)> {
+    let longest = runs.iter().map(|r| r.net_by_round.len()).max().unwrap_or(0);
+    (0..longest).map(|i| {
+        let mut values: Vec<f64> = runs.iter().filter_map(|r| r.net_by_round.get(i).copied()).collect();
+        values.sort_by(f64::total_cmp);
+        let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        (i, mean, percentile(0.1), percentile(0.9))
+    }).collect()
+}
+
+// Overlays several runs' net results, one legend entry per label. With `bands`, runs sharing a
+// label are drawn as their mean with a 10th-90th percentile band.
+pub fn generate_comparison_graph(series: &[RunSeries], bands: bool, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if series.is_empty() {
+        println!("Nothing to compare.");
+        return Ok(());
+    }
+    let mut labels: Vec<&str> = Vec::new();
+    for run in series {
+        if !labels.contains(&run.label.as_str()) {
+            labels.push(&run.label);
+        }
+    }
+    let longest = series.iter().map(|r| r.net_by_round.len()).max().unwrap_or(0).max(2);
+    let all_values = series.iter().flat_map(|r| r.net_by_round.iter().copied());
+    let (min_net, max_net) = all_values.fold((0.0f64, 0.0f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let padding = ((max_net - min_net) * 0.1).max(10.0);
+
+    let title = if bands { "Net Result by Round: Group Mean with 10th-90th Percentile" } else { "Net Result by Round" };
+    draw_to_file!(path, (1200, 600), |root_area| {
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 30).into_font())
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0..longest - 1, (min_net - padding)..(max_net + padding))?;
+        chart.configure_mesh()
+            .x_desc("Hand Number (0 = Initial State)")
+            .y_desc("Net Result ($)")
+            .draw()?;
+
+        for (color_idx, label) in labels.iter().enumerate() {
+            let color = Palette99::pick(color_idx).to_rgba();
+            let group: Vec<&RunSeries> = series.iter().filter(|r| r.label == *label).collect();
+            let legend = format!("{} ({} run{})", label, group.len(), if group.len() == 1 { "" } else { "s" });
+            if bands {
+                let stats = group_bands(&group);
+                let band: Vec<(usize, f64)> = stats.iter().map(|&(i, _, _, high)| (i, high))
+                    .chain(stats.iter().rev().map(|&(i, _, low, _)| (i, low)))
+                    .collect();
+                chart.draw_series(std::iter::once(Polygon::new(band, color.mix(0.2).filled())))?;
+                chart.draw_series(LineSeries::new(stats.iter().map(|&(i, mean, _, _)| (i, mean)), color.stroke_width(2)))?
+                    .label(legend)
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.filled()));
+            } else {
+                for (run_idx, run) in group.iter().enumerate() {
+                    let drawn = chart.draw_series(LineSeries::new(
+                        run.net_by_round.iter().enumerate().map(|(i, &net)| (i, net)), color.mix(0.8).stroke_width(2),
+                    ))?;
+                    if run_idx == 0 {
+                        drawn.label(legend.clone())
+                            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.filled()));
+                    }
+                }
+            }
+        }
+        chart.draw_series(LineSeries::new(vec![(0, 0.0), (longest - 1, 0.0)], BLACK.mix(0.5)))?;
+
+        chart.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    });
+
+    let run_ids: Vec<String> = series.iter().map(|r| r.run_id.to_string()).collect();
+    log::info!("Comparison graph of runs {} saved to: {:?}", run_ids.join(", "), path);
+    println!("Comparison graph saved to: {:?}", path);
+    Ok(())
+}
+
+// Bars of a histogram: (low edge, high edge, count)
+fn draw_histogram(path: &Path, title: &str, x_desc: &str, bins: &[(f64, f64, u32)], color: RGBColor) -> Result<(), Box<dyn std::error::Error>> {
+    let x_min = bins.iter().map(|b| b.0).fold(f64::INFINITY, f64::min);
+    let x_max = bins.iter().map(|b| b.1).fold(f64::NEG_INFINITY, f64::max);
+    let max_count = bins.iter().map(|b| b.2).max().unwrap_or(1).max(1);
+    let x_padding = ((x_max - x_min) * 0.03).max(0.1);
+
+    draw_to_file!(path, (1200, 600), |root_area| {
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 30).into_font())
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d((x_min - x_padding)..(x_max + x_padding), 0u32..max_count + max_count / 10 + 1)?;
+        chart.configure_mesh()
+            .x_desc(x_desc)
+            .y_desc("Count")
+            .draw()?;
+        chart.draw_series(bins.iter().map(|&(low, high, count)| {
+            Rectangle::new([(low, 0), (high, count)], color.mix(0.8).filled())
+        }))?;
+    });
     Ok(())
 }
+
+// Splits values into `num_bins` equal-width bins between their min and max
+fn equal_width_bins(values: &[f64], num_bins: usize) -> Vec<(f64, f64, u32)> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = ((max - min) / num_bins as f64).max(1e-9);
+    let mut counts = vec![0u32; num_bins];
+    for &v in values {
+        counts[(((v - min) / width) as usize).min(num_bins - 1)] += 1;
+    }
+    counts.into_iter().enumerate()
+        .map(|(i, count)| (min + i as f64 * width, min + (i + 1) as f64 * width, count))
+        .collect()
+}
+
+// Net result of each round in multiples of the bet, from the balance after each round
+pub fn round_results_in_bets(balance_history: &[f64], bet: f64) -> Vec<f64> {
+    balance_history.windows(2).map(|pair| (pair[1] - pair[0]) / bet).collect()
+}
+
+// Round results counted per half bet (key = result in half bets), the histogram's bins
+pub fn half_bet_counts(round_results_bets: &[f64]) -> BTreeMap<i64, u32> {
+    let mut counts = BTreeMap::new();
+    for &result in round_results_bets {
+        *counts.entry((result * 2.0).round() as i64).or_insert(0) += 1;
+    }
+    counts
+}
+
+// Net result of each round in bets; one bar per half bet, so pushes, wins, blackjacks and doubles stand apart
+pub fn generate_round_result_histogram(round_results_bets: &[f64], run_timestamp: u64) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    if round_results_bets.is_empty() {
+        return Ok(None);
+    }
+    let bins: Vec<(f64, f64, u32)> = half_bet_counts(round_results_bets).into_iter()
+        .map(|(half_bets, count)| (half_bets as f64 / 2.0 - 0.2, half_bets as f64 / 2.0 + 0.2, count))
+        .collect();
+    let path = run_graph_path(run_timestamp, "round_results");
+    let title = format!("Net Result per Round ({} rounds, Run ID: {})", round_results_bets.len(), run_timestamp);
+    draw_histogram(&path, &title, "Net Result (bets)", &bins, BLUE)?;
+    log::info!("Round result histogram saved to: {:?}", path);
+    Ok(Some(path))
+}
+
+// Cuts the balance history into sessions of `session_hands` rounds and charts how they ended
+pub fn generate_session_outcome_histogram(balance_history: &[f64], session_hands: usize, run_timestamp: u64) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let outcomes: Vec<f64> = balance_history.iter().step_by(session_hands.max(1)).collect::<Vec<_>>()
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect();
+    if outcomes.len() < 2 {
+        log::info!("Fewer than two full {}-hand sessions; skipping session outcome histogram.", session_hands);
+        return Ok(None);
+    }
+    let winning = outcomes.iter().filter(|&&o| o > 0.0).count();
+    let summary = format!("Sessions of {} hands: {}, winning: {} ({:.1}%), best ${:+.2}, worst ${:+.2}",
+        session_hands, outcomes.len(), winning, winning as f64 / outcomes.len() as f64 * 100.0,
+        outcomes.iter().copied().fold(f64::NEG_INFINITY, f64::max), outcomes.iter().copied().fold(f64::INFINITY, f64::min));
+    println!("{}", summary);
+    log::info!("{}", summary);
+
+    let path = run_graph_path(run_timestamp, "sessions");
+    let title = format!("Outcome of {} Sessions of {} Hands (Run ID: {})", outcomes.len(), session_hands, run_timestamp);
+    draw_histogram(&path, &title, "Session Net Result ($)", &equal_width_bins(&outcomes, 20.min(outcomes.len())), GREEN)?;
+    log::info!("Session outcome histogram saved to: {:?}", path);
+    Ok(Some(path))
+}
+
+// Underwater chart: how far the balance sits below its running peak after each hand
+pub fn generate_drawdown_chart(balance_history: &[f64], run_timestamp: u64) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    if balance_history.len() < 2 {
+        return Ok(None);
+    }
+    let mut peak = f64::NEG_INFINITY;
+    let drawdowns: Vec<f64> = balance_history.iter().map(|&balance| {
+        peak = peak.max(balance);
+        balance - peak
+    }).collect();
+    let max_drawdown = drawdowns.iter().copied().fold(0.0, f64::min);
+    let summary = format!("Max Drawdown (You): ${:.2}", -max_drawdown);
+    println!("{}", summary);
+    log::info!("{}", summary);
+
+    let path = run_graph_path(run_timestamp, "drawdown");
+    draw_to_file!(&path, (1200, 600), |root_area| {
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(format!("Drawdown from Peak Balance (Run ID: {})", run_timestamp), ("sans-serif", 30).into_font())
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0..drawdowns.len() - 1, (max_drawdown * 1.1).min(-1.0)..0.0)?;
+        chart.configure_mesh()
+            .x_desc("Hand Number (0 = Initial State)")
+            .y_desc("Below Peak ($)")
+            .draw()?;
+        chart.draw_series(AreaSeries::new(drawdowns.iter().copied().enumerate(), 0.0, RED.mix(0.3)).border_style(RED))?;
+    });
+    log::info!("Drawdown chart saved to: {:?}", path);
+    Ok(Some(path))
+}
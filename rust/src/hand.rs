@@ -1,9 +1,11 @@
 // src/hand.rs
 //use crate::card_deck::{Card, Rank};
 use crate::card_deck::{Card};
+use crate::money::Money;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)] // Added Eq for easier comparison in some cases
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)] // Added Eq for easier comparison in some cases
 pub enum HandStatus {
     Active,    // Still playing
     Stood,     // Player chose to stand
@@ -24,28 +26,96 @@ impl fmt::Display for HandStatus {
     }
 }
 
+// Who made a playing decision for a hand
+#[allow(dead_code)] // IndexPlay is reserved for count-based deviations from the Book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionSource {
+    User,      // Typed in by "You"
+    Book,      // Basic strategy chart
+    IndexPlay, // Count-based deviation from the Book
+    Profile,   // An AI seat's non-Book behaviour profile
+}
+
+impl fmt::Display for DecisionSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecisionSource::User => write!(f, "User"),
+            DecisionSource::Book => write!(f, "Book"),
+            DecisionSource::IndexPlay => write!(f, "Index Play"),
+            DecisionSource::Profile => write!(f, "Profile"),
+        }
+    }
+}
+
+// One entry in a hand's history, in the order it happened
+#[allow(dead_code)] // Surrender is not offered yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HandEvent {
+    Dealt(Card),                                       // Any card added to the hand
+    SplitFrom { parent_hand: usize, card: Card },      // Hand created by splitting hand `parent_hand` (1-based)
+    Hit(DecisionSource),
+    Stand(DecisionSource),
+    Double(DecisionSource),
+    Split(DecisionSource),
+    Surrender(DecisionSource),
+    Insurance { taken: bool },
+    BookModeChosen,                                    // "You" handed the rest of the round to the Book
+}
+
 #[derive(Debug, Clone)]
 pub struct Hand {
     pub cards: Vec<Card>,
-    pub bet: f64, // Only "Your" player's bet is financially tracked
+    pub bet: Money, // Only "Your" player's bet is financially tracked
     pub status: HandStatus,
     pub is_split_ace: bool,
-    pub actions_taken: Vec<String>, // For potential detailed logging or complex strategy
+    pub history: Vec<HandEvent>,
+    pub net_result: Money, // Settled win (+) or loss (-) for the round; only tracked for "You"
 }
 
 impl Hand {
-    pub fn new(initial_bet: f64) -> Self {
+    pub fn new(initial_bet: Money) -> Self {
         Hand {
             cards: Vec::new(),
             bet: initial_bet,
             status: HandStatus::Active,
             is_split_ace: false,
-            actions_taken: Vec::new(),
+            history: Vec::new(),
+            net_result: Money::ZERO,
         }
     }
 
+    // Second hand of a split: starts with the card moved over from `parent_hand` (1-based)
+    pub fn new_split_from(parent_hand: usize, moved_card: Card, bet: Money) -> Self {
+        let mut hand = Hand::new(bet);
+        hand.cards.push(moved_card);
+        hand.history.push(HandEvent::SplitFrom { parent_hand, card: moved_card });
+        hand
+    }
+
     pub fn add_card(&mut self, card: Card) {
         self.cards.push(card);
+        self.history.push(HandEvent::Dealt(card));
+    }
+
+    pub fn record(&mut self, event: HandEvent) {
+        self.history.push(event);
+    }
+
+    pub fn was_split(&self) -> bool {
+        self.history.iter().any(|e| matches!(e, HandEvent::Split(_) | HandEvent::SplitFrom { .. }))
+    }
+
+    pub fn was_doubled(&self) -> bool {
+        self.history.iter().any(|e| matches!(e, HandEvent::Double(_)))
+    }
+
+    // The bet before any double down
+    pub fn original_bet(&self) -> Money {
+        if self.was_doubled() { self.bet / 2 } else { self.bet }
+    }
+
+    pub fn book_mode_chosen(&self) -> bool {
+        self.history.contains(&HandEvent::BookModeChosen)
     }
 
     pub fn value(&self) -> u8 {
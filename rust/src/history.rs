@@ -0,0 +1,165 @@
+// src/history.rs
+use crate::card_deck::{Card, Deck};
+use crate::config;
+use crate::hand::{Hand, HandEvent, HandStatus};
+use crate::money::Money;
+use crate::player::{Dealer, Player};
+use crate::stats::SessionStats;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Where the shoe stood when a round was dealt
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ShoePosition {
+    pub cards_remaining: usize,
+    pub shoe_size: usize,
+}
+
+impl ShoePosition {
+    pub fn of(deck: &Deck) -> Self {
+        ShoePosition { cards_remaining: deck.len(), shoe_size: deck.initial_size }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandRecord {
+    pub cards: Vec<Card>,
+    pub events: Vec<HandEvent>, // Cards dealt and decisions with their source, in order
+    pub status: HandStatus,
+    pub bet: Money,
+    pub net_result: Money,
+}
+
+impl HandRecord {
+    fn from_hand(hand: &Hand) -> Self {
+        HandRecord {
+            cards: hand.cards.clone(),
+            events: hand.history.clone(),
+            status: hand.status.clone(),
+            bet: hand.bet,
+            net_result: hand.net_result,
+        }
+    }
+
+    // The bet when the decisions on this hand were made, before any double down
+    pub fn original_bet(&self) -> Money {
+        self.to_hand().original_bet()
+    }
+
+    pub fn to_hand(&self) -> Hand {
+        let mut hand = Hand::new(self.bet);
+        hand.cards = self.cards.clone();
+        hand.status = self.status.clone();
+        hand.history = self.events.clone();
+        hand.net_result = self.net_result;
+        hand
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatRecord {
+    pub seat: usize, // 1-based, as shown at the table
+    pub name: String,
+    pub is_user: bool,
+    pub ai_profile: Option<String>, // None for "You"
+    pub hands: Vec<HandRecord>,
+}
+
+// One finished round. Money amounts are in cents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub run_id: u64,
+    pub round: u32, // "Your" main hand number within the session
+    pub mode: String,
+    pub rules: String,
+    pub shoe: ShoePosition,
+    pub seats: Vec<SeatRecord>,
+    pub dealer_cards: Vec<Card>,
+    pub dealer_value: u8,
+    pub balance_before: Money,
+    pub balance_after: Money,
+}
+
+impl RoundRecord {
+    pub fn capture(
+        session_stats: &SessionStats,
+        shoe_at_deal: ShoePosition,
+        players: &[Player],
+        dealer: &Dealer,
+        balance_before: Money,
+        balance_after: Money,
+    ) -> Self {
+        RoundRecord {
+            run_id: session_stats.run_id,
+            round: session_stats.hands_played_session,
+            mode: session_stats.mode.clone(),
+            rules: config::rules_summary(),
+            shoe: shoe_at_deal,
+            seats: players.iter().map(|p| SeatRecord {
+                seat: p.seat + 1,
+                name: p.name.clone(),
+                is_user: p.is_user,
+                ai_profile: if p.is_user { None } else { Some(p.ai_profile.to_string()) },
+                hands: p.hands.iter().map(HandRecord::from_hand).collect(),
+            }).collect(),
+            dealer_cards: dealer.hand.cards.clone(),
+            dealer_value: dealer.hand.value(),
+            balance_before,
+            balance_after,
+        }
+    }
+}
+
+pub fn hand_history_path(run_id: u64) -> PathBuf {
+    PathBuf::from(&config::settings().output_dir).join(format!("{}{}", run_id, config::HAND_HISTORY_SUFFIX))
+}
+
+// Newest hand history file in the logs directory, if any
+pub fn latest_hand_history_file() -> Option<PathBuf> {
+    fs::read_dir(&config::settings().output_dir).ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.to_string_lossy().ends_with(config::HAND_HISTORY_SUFFIX))
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+pub fn read_hand_history(path: &Path) -> Result<Vec<RoundRecord>, Box<dyn std::error::Error>> {
+    let mut rounds = Vec::new();
+    for (line_no, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line).map_err(|e| format!("{}:{}: {}", path.display(), line_no + 1, e))?;
+        rounds.push(record);
+    }
+    Ok(rounds)
+}
+
+// "Your" net result after each round, starting from 0 before the first one
+pub fn net_balance_series(rounds: &[RoundRecord]) -> Vec<f64> {
+    let Some(first) = rounds.first() else { return Vec::new(); };
+    let start = first.balance_before;
+    std::iter::once(0.0).chain(rounds.iter().map(|r| (r.balance_after - start).as_f64())).collect()
+}
+
+// Appends one JSON line per round; a resumed session keeps writing to its original file
+pub struct HandHistoryWriter {
+    file: BufWriter<File>,
+}
+
+impl HandHistoryWriter {
+    pub fn open(run_id: u64) -> Result<Self, Box<dyn std::error::Error>> {
+        fs::create_dir_all(&config::settings().output_dir)?;
+        let file = OpenOptions::new().create(true).append(true).open(hand_history_path(run_id))?;
+        Ok(HandHistoryWriter { file: BufWriter::new(file) })
+    }
+
+    pub fn write_round(&mut self, record: &RoundRecord) -> Result<(), Box<dyn std::error::Error>> {
+        serde_json::to_writer(&mut self.file, record)?;
+        self.file.write_all(b"\n")?;
+        self.file.flush()?; // Keep the file complete up to the last round if the program is killed
+        Ok(())
+    }
+}
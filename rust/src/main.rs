@@ -10,14 +10,35 @@ mod game_logic;
 mod stats;
 mod graph;
 mod utils;
+mod money;
+mod trainer;
+mod drill;
+mod profile;
+mod session;
+mod history;
+mod replay;
+mod cli;
+mod report;
+mod ev_matrix;
+mod terminal_chart;
+mod results_db;
 
 // USE STATEMENTS to bring items into the main.rs scope
-use crate::card_deck::Deck; // Use `crate::` prefix for clarity, assumes modules are direct children of src
-use crate::player::{Player, Dealer};
+use crate::card_deck::{Deck, load_shoe_file}; // Use `crate::` prefix for clarity, assumes modules are direct children of src
+use crate::player::{Player, Dealer, TableLayout};
 use crate::stats::{SessionStats, setup_logger};
-use crate::graph::generate_balance_graph; // Specific function for graph
+use crate::graph::{generate_balance_graph, BalanceEvent}; // Specific functions for graph
 use crate::game_logic::play_blackjack_round; // Specific function for playing a round
+use crate::money::smallest_chip;
+use crate::trainer::TrainerStats;
+use crate::session::SavedSession;
+use crate::history::{HandHistoryWriter, RoundRecord, ShoePosition};
 
+use crate::cli::{Cli, Command, PlayArgs, ProfilesCommand, RunsCommand, SimulateArgs};
+use crate::report::SessionReport;
+
+use clap::Parser;
+use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::time::Instant;
 
@@ -25,17 +46,33 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let script_start_time = Instant::now();
     let run_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
+    let cli = Cli::parse();
+    let settings = cli.settings();
+    settings.validate()?;
+    config::install_settings(settings);
+
     if let Err(e) = setup_logger(run_timestamp) {
         eprintln!("Failed to initialize logger: {}. Game will continue without text logging.", e);
     }
 
-    // Use log facade after logger is set up
-    log::info!("--- New Game Session Started (Payout: {}/{}, Total Players: {}) ---",
-        config::BLACKJACK_PAYOUT_NUMERATOR, config::BLACKJACK_PAYOUT_DENOMINATOR, config::NUM_PLAYERS);
+    let table_layout = TableLayout::from_config();
+    table_layout.validate()?;
+    let chip_unit = smallest_chip(config::CHIP_DENOMINATIONS);
 
-    println!("--- Welcome to {}-Deck Blackjack! (RUN ID: {}) ---", config::NUM_DECKS, run_timestamp);
-    println!("Blackjack Payout: {}/{}", config::BLACKJACK_PAYOUT_NUMERATOR, config::BLACKJACK_PAYOUT_DENOMINATOR);
-    println!("Total Players at Table (incl. You): {}", config::NUM_PLAYERS);
+    // Use log facade after logger is set up
+    log::info!("--- New Game Session Started (Payout: {}/{}, Seats: {}, Occupied: {}, Your Seat: {}) ---",
+        config::settings().blackjack_payout_numerator, config::settings().blackjack_payout_denominator,
+        table_layout.num_seats, table_layout.num_occupied_seats(), table_layout.your_seat + 1);
+
+    println!("--- Welcome to {}-Deck Blackjack! (RUN ID: {}) ---", config::settings().num_decks, run_timestamp);
+    println!("Blackjack Payout: {}/{} (rounded down to ${} chips)",
+        config::settings().blackjack_payout_numerator, config::settings().blackjack_payout_denominator, chip_unit);
+    println!("Total Players at Table (incl. You): {} of {} seats", table_layout.num_occupied_seats(), table_layout.num_seats);
+    println!("You are sitting at {}", table_layout.seat_label(table_layout.your_seat));
+    if let Some(seed) = config::settings().seed {
+        println!("Shoe Seed: {}", seed);
+        log::info!("Shoe Seed: {}", seed);
+    }
 
     // Check if plotting is available (assuming graph.rs might expose such a const or function)
     // For simplicity, we'll directly reference the const as if it were in graph module.
@@ -44,57 +81,217 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Note: Plotting library backend issue. Balance graph will not be generated for simulations.");
     }
 
-    let game_mode_input = utils::get_user_input(
-        "Choose mode: (i)nteractive or (s)imulation (for 'Your' play)? ",
-    );
-    let is_simulation_for_user_player = game_mode_input == "s";
+    let command = match cli.command {
+        Some(command) => command,
+        None => cli::prompt_for_command(),
+    };
+    let mut play_args = PlayArgs::default();
+    let mut sim_args = SimulateArgs::default();
+    let is_simulation_for_user_player = match command {
+        Command::Profiles(args) => {
+            match args.command {
+                Some(ProfilesCommand::List) => profile::print_profiles()?,
+                Some(ProfilesCommand::Reset { name }) => profile::reset_profile(&name)?,
+                Some(ProfilesCommand::Delete { name }) => profile::remove_profile(&name)?,
+                None => profile::run_profile_menu()?,
+            }
+            return Ok(());
+        }
+        Command::Replay(args) => {
+            if let Some(path) = resolve_hand_history_file(args.file) {
+                replay::run_replay(&path)?;
+            }
+            return Ok(());
+        }
+        Command::Analyze(args) => {
+            if let Some(path) = resolve_hand_history_file(args.file) {
+                replay::run_analyze(&path)?;
+            }
+            return Ok(());
+        }
+        Command::Runs(args) => {
+            match args.command {
+                RunsCommand::List(list) => results_db::list_runs(&results_db::RunFilter {
+                    rules: list.rules, mode: list.mode, since: list.since, limit: list.limit,
+                })?,
+                RunsCommand::Diff { run_a, run_b } => results_db::diff_runs(run_a, run_b)?,
+            }
+            return Ok(());
+        }
+        Command::Compare(args) => {
+            let mut series = Vec::new();
+            for (i, file) in args.files.iter().enumerate() {
+                let rounds = history::read_hand_history(file)?;
+                let Some(first) = rounds.first() else {
+                    println!("No rounds in {}; skipping it.", file.display());
+                    continue;
+                };
+                let label = args.labels.get(i).cloned().unwrap_or_else(|| first.rules.clone());
+                series.push(graph::RunSeries { label, run_id: first.run_id, net_by_round: history::net_balance_series(&rounds) });
+            }
+            let out = args.out.unwrap_or_else(|| PathBuf::from(&config::settings().output_dir)
+                .join(format!("compare_{}.{}", run_timestamp, config::settings().graph_format.extension())));
+            graph::generate_comparison_graph(&series, args.bands, &out)?;
+            return Ok(());
+        }
+        Command::Drill(args) => {
+            log::info!("Mode Selected: Drill");
+            let mut player_profile = profile::profile_from_args(&args.profile)?;
+            let past_misses = player_profile.as_ref().map(|p| p.drill_misses.clone()).unwrap_or_default();
+            let drill_report = drill::run_drill_menu(&args, &past_misses);
+            if let Some(profile) = player_profile.as_mut() {
+                profile.record_accuracy(run_timestamp, &drill_report.name, drill_report.questions, drill_report.correct);
+                profile.record_drill_misses(&drill_report.misses_per_cell);
+                profile::save_profile(profile)?;
+                println!("Drill accuracy saved to profile '{}'.", profile.name);
+            }
+            log::info!("--- Session Ended (RUN ID: {}) ---", run_timestamp);
+            return Ok(());
+        }
+        Command::Play(args) => {
+            play_args = args;
+            false
+        }
+        Command::Simulate(args) => {
+            sim_args = args;
+            true
+        }
+    };
+    let (resumed_session, resumed_save_path) = if play_args.resume {
+        match session::prompt_for_saved_session(&table_layout)? {
+            Some((path, saved)) => (Some(saved), Some(path)),
+            None => return Ok(()),
+        }
+    } else {
+        (None, None)
+    };
+    // Simulations always start from the configured bankroll; profiles track a person's own play
+    let mut player_profile = match &resumed_session {
+        Some(saved) => match &saved.profile_name {
+            Some(name) => Some(profile::load_profile(name)?.unwrap_or_else(|| profile::PlayerProfile::new(name))),
+            None => None,
+        },
+        None if is_simulation_for_user_player => None,
+        None => profile::profile_from_args(&play_args.profile)?,
+    };
+    let is_new_interactive = !is_simulation_for_user_player && resumed_session.is_none();
+    let is_practice_session = is_new_interactive && play_args.practice.unwrap_or_else(|| utils::get_user_input(
+        "Practice mode? Lets you undo decisions; results are flagged as practice (y/n): ",
+    ) == "y");
+    // A saved session's result replaces the profile balance when it is finished, which would
+    // drop whatever a new session on the same profile won or lost in the meantime
+    if let Some(profile) = player_profile.as_ref().filter(|_| is_new_interactive && !is_practice_session) {
+        if let Some(saved) = session::saved_session_for_profile(&profile.name)? {
+            return Err(format!("Profile '{}' has a saved session ({}). Resume it with `play --resume` first, or play it as practice.",
+                profile.name, saved.summary_line()).into());
+        }
+    }
+    let is_trainer_session = is_new_interactive && play_args.trainer.unwrap_or_else(|| utils::get_user_input(
+        "Trainer mode? Grades each of your decisions against the Book (y/n): ",
+    ) == "y");
 
-    let mut your_player_balance = config::STARTING_BALANCE;
+    let mut your_player_balance = match &player_profile {
+        Some(profile) => profile.balance,
+        None => config::settings().starting_balance,
+    };
+    let ai_mode_desc = if table_layout.all_ai_play_book() { "Book" } else { "Mixed Profiles" };
     let mut session_stats = SessionStats::new(
         run_timestamp,
-        if is_simulation_for_user_player { "Simulation (You: Book, AI: Book)".to_string() }
-        else { "Interactive (You: Manual/Book, AI: Book)".to_string() },
-        config::STARTING_BALANCE,
+        if is_simulation_for_user_player { format!("Simulation (You: Book, AI: {})", ai_mode_desc) }
+        else {
+            format!("Interactive{}{} (You: Manual/Book, AI: {})",
+                if is_practice_session { " Practice" } else { "" },
+                if is_trainer_session { " Trainer" } else { "" },
+                ai_mode_desc)
+        },
+        your_player_balance,
     );
+    session_stats.practice_mode = is_practice_session;
+    if is_trainer_session {
+        session_stats.trainer_stats = Some(TrainerStats::default());
+    }
+    session_stats.ai_seat_profiles = table_layout.ai_profiles_summary();
+    let mut deck = match &config::settings().stacked_shoe_file {
+        Some(shoe_file) => Deck::from_cards(load_shoe_file(std::path::Path::new(shoe_file))?, config::settings().seed),
+        None => Deck::new(config::settings().num_decks, config::settings().seed),
+    };
+    let mut resumed_balance_history = None;
+    let mut resumed_balance_events = Vec::new();
+    if let Some(saved) = resumed_session {
+        println!("--- Resuming session {} ---", saved.summary_line());
+        log::info!("Resuming saved session (original RUN ID: {})", saved.session_stats.run_id);
+        your_player_balance = saved.your_balance;
+        session_stats = saved.session_stats;
+        deck = saved.deck;
+        resumed_balance_history = Some(saved.balance_history);
+        resumed_balance_events = saved.balance_events;
+    }
+    // Every file of a session (graphs, EV matrix, report, hand history) is named after this id,
+    // which a resumed session keeps from its first run
+    let session_run_id = session_stats.run_id;
 
     log::info!("Mode Selected (for 'Your' play): {}", session_stats.mode);
-    log::info!("Starting Balance (You): ${:.2}", your_player_balance);
-    log::info!("Configured Default Bet (You): ${:.2}", config::DEFAULT_BET);
+    if let Some(profile) = &player_profile {
+        log::info!("Player Profile: {}", profile.name);
+    }
+    log::info!("AI Seat Profiles: {}", session_stats.ai_seat_profiles);
+    log::info!("Starting Balance (You): ${}", your_player_balance);
+    log::info!("Configured Default Bet (You): ${}", config::settings().default_bet);
+
+    // Keyed by the session's own run id so a resumed session appends to the same file
+    let mut hand_history = match HandHistoryWriter::open(session_stats.run_id) {
+        Ok(writer) => Some(writer),
+        Err(e) => {
+            log::error!("Failed to open hand history file: {}", e);
+            eprintln!("Failed to open hand history file: {}. Continuing without hand history.", e);
+            None
+        }
+    };
 
-    let mut deck = Deck::new(config::NUM_DECKS);
-    println!("--- Initializing a new {}-deck shoe with {} cards. ---", config::NUM_DECKS, deck.initial_size);
+    if resumed_balance_history.is_some() {
+        println!("--- Continuing the {}-deck shoe with {} of {} cards left. ---", config::settings().num_decks, deck.len(), deck.initial_size);
+    } else if let Some(shoe_file) = &config::settings().stacked_shoe_file {
+        println!("--- Dealing a stacked shoe of {} cards from {}. ---", deck.initial_size, shoe_file);
+        log::info!("Stacked shoe: {} ({} cards)", shoe_file, deck.initial_size);
+    } else {
+        println!("--- Initializing a new {}-deck shoe with {} cards. ---", config::settings().num_decks, deck.initial_size);
+    }
     utils::sleep_ms(utils::get_delay_multiplied(500, false)); // Delay not dependent on sim active yet
 
 
+    let mut session_saved = false;
+    let mut graph_paths: Vec<(String, PathBuf)> = Vec::new(); // (title, file) for the HTML report
+    let session_balance_history: Vec<f64>; // For the terminal charts
     if is_simulation_for_user_player {
-        let num_iterations = utils::get_num_iterations(config::DEFAULT_SIM_ITERATIONS);
+        let num_iterations = sim_args.iterations.unwrap_or_else(|| utils::get_num_iterations(config::DEFAULT_SIM_ITERATIONS));
         session_stats.target_iterations = Some(num_iterations);
+        session_stats.swings.session_hands = sim_args.session_hands.unwrap_or(config::SESSION_LENGTH_HANDS);
         println!("\nStarting simulation for {} hands. 'You' will play by Book strategy.", num_iterations);
         log::info!("Simulation Target Iterations: {}", num_iterations);
 
-        let mut balance_history_sim: Vec<f64> = vec![your_player_balance];
+        let mut balance_history_sim: Vec<f64> = vec![your_player_balance.as_f64()];
 
         for i in 0..num_iterations {
-            println!("\n--- Sim Hand #{} / {} | Your Bal: ${:.2} ---", i + 1, num_iterations, your_player_balance);
+            println!("\n--- Sim Hand #{} / {} | Your Bal: ${} ---", i + 1, num_iterations, your_player_balance);
             log::info!("Starting Sim Hand #{}", i + 1);
 
-            if deck.needs_reshuffle(config::RESHUFFLE_THRESHOLD_RATIO) {
+            if deck.needs_reshuffle(config::settings().reshuffle_threshold_ratio) {
                 println!("--- Shoe penetration low ({} cards left). Reshuffling... ---", deck.len());
                 log::info!("Reshuffling shoe. Cards left: {}", deck.len());
-                deck = Deck::new(config::NUM_DECKS);
+                deck.reshuffle();
+                session_stats.swings.start_new_shoe();
                 println!("--- New shoe shuffled with {} cards. ---", deck.initial_size);
                 utils::sleep_ms(utils::get_delay_multiplied(500, true));
             }
 
-            let mut all_players_at_table: Vec<Player> = Vec::new();
-            all_players_at_table.push(Player::new_user(0, "Your".to_string(), 0.0));
-            for p_id in 1..config::NUM_PLAYERS {
-                all_players_at_table.push(Player::new_ai(p_id, format!("Player {}", p_id + 1)));
-            }
+            deck.start_round();
+            let mut all_players_at_table: Vec<Player> = table_layout.build_players();
             let mut dealer = Dealer::new();
+            let shoe_at_deal = ShoePosition::of(&deck);
+            let balance_before_round = your_player_balance;
 
-            if your_player_balance < config::DEFAULT_BET {
-                let msg = format!("Your Balance (${:.2}) < Default Bet (${:.2}). Sim ends.", your_player_balance, config::DEFAULT_BET);
+            if your_player_balance < config::settings().default_bet {
+                let msg = format!("Your Balance (${}) < Default Bet (${}). Sim ends.", your_player_balance, config::settings().default_bet);
                 println!("{}", msg); log::warn!("Sim ended early at hand {}: {}", i + 1, msg);
                 break;
             }
@@ -106,73 +303,126 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 &mut your_player_balance,
                 &mut session_stats,
                 true, // is_simulation_round = true
-                run_timestamp,
-                0.02, // delay_multiplier
+                session_run_id,
             ) {
                 let msg = "Could not place Your bet (sim). Sim ends.";
                 println!("{}", msg); log::warn!("Sim ended early at hand {}: {}", i + 1, msg);
                 break;
             }
+            write_hand_history(&mut hand_history, RoundRecord::capture(
+                &session_stats, shoe_at_deal, &all_players_at_table, &dealer, balance_before_round, your_player_balance,
+            ));
 
-            balance_history_sim.push(your_player_balance);
+            balance_history_sim.push(your_player_balance.as_f64());
+            record_round_results(&mut session_stats, &all_players_at_table, &dealer, your_player_balance - balance_before_round);
             session_stats.update_balance_extremes(your_player_balance);
 
             if your_player_balance < config::MIN_BET_ALLOWED {
-                let msg = format!("Your Balance (${:.2}) < Min Bet (${:.2}). Sim ends.", your_player_balance, config::MIN_BET_ALLOWED);
+                let msg = format!("Your Balance (${}) < Min Bet (${}). Sim ends.", your_player_balance, config::MIN_BET_ALLOWED);
                 println!("{}", msg); log::warn!("Sim ended early at hand {}: {}", i + 1, msg);
                 break;
             }
-            log::info!("Finished Sim Hand #{}. Your Balance: ${:.2}", i + 1, your_player_balance);
+            log::info!("Finished Sim Hand #{}. Your Balance: ${}", i + 1, your_player_balance);
         }
 
-        if let Err(e) = generate_balance_graph(&balance_history_sim, run_timestamp, session_stats.initial_balance) {
-            log::error!("Failed to generate balance graph: {}", e);
-            eprintln!("Error generating balance graph: {}", e);
+        match generate_balance_graph(&balance_history_sim, &[], &session_stats.mode, session_stats.run_id, session_stats.initial_balance.as_f64()) {
+            Ok(Some(path)) => graph_paths.push(("Balance".to_string(), path)),
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to generate balance graph: {}", e);
+                eprintln!("Error generating balance graph: {}", e);
+            }
+        }
+        let round_results_bets = graph::round_results_in_bets(&balance_history_sim, session_stats.initial_default_bet.as_f64());
+        match graph::generate_round_result_histogram(&round_results_bets, session_stats.run_id) {
+            Ok(Some(path)) => {
+                println!("Round result histogram saved to: {:?}", path);
+                graph_paths.push(("Net Result per Round".to_string(), path));
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to generate round result histogram: {}", e),
+        }
+        let session_hands = sim_args.session_hands.unwrap_or(config::SESSION_LENGTH_HANDS) as usize;
+        match graph::generate_session_outcome_histogram(&balance_history_sim, session_hands, session_stats.run_id) {
+            Ok(Some(path)) => {
+                println!("Session outcome histogram saved to: {:?}", path);
+                graph_paths.push((format!("Outcome of {}-Hand Sessions", session_hands), path));
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to generate session outcome histogram: {}", e),
+        }
+        match graph::generate_drawdown_chart(&balance_history_sim, session_stats.run_id) {
+            Ok(Some(path)) => {
+                println!("Drawdown chart saved to: {:?}", path);
+                graph_paths.push(("Drawdown from Peak Balance".to_string(), path));
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to generate drawdown chart: {}", e),
+        }
+        match graph::generate_strategy_heatmap(session_stats.run_id) {
+            Ok(path) => {
+                println!("Strategy heatmap saved to: {:?}", path);
+                graph_paths.push(("Basic Strategy".to_string(), path));
+            }
+            Err(e) => log::error!("Failed to generate strategy heatmap: {}", e),
+        }
+        match graph::generate_ev_heatmap(&session_stats.ev_matrix, session_stats.run_id) {
+            Ok(Some(path)) => {
+                println!("EV heatmap saved to: {:?}", path);
+                graph_paths.push(("EV by Starting Hand vs Dealer Upcard".to_string(), path));
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to generate EV heatmap: {}", e),
         }
 
+        session_balance_history = balance_history_sim;
         session_stats.final_balance = your_player_balance;
         session_stats.calculate_final_metrics();
 
         println!("\n\n--- Simulation Finished (Your Play: Book) ---");
         log::info!("--- Simulation Results (Your Play: Book) ---");
-        for line in session_stats.to_log_lines() {
+        for line in session_stats.to_log_lines().into_iter().chain(session_stats.ev_matrix.to_log_lines()) {
             println!("{}", line);
             log::info!("{}", line);
         }
 
     } else { // Interactive Mode for "Your" play
-        println!("\nStarting interactive game for 'You'. Other {} player(s) will play by Book.",
-            if config::NUM_PLAYERS > 0 { config::NUM_PLAYERS.saturating_sub(1) } else { 0 });
+        println!("\nStarting interactive game for 'You'. Other {} player(s): {}.",
+            table_layout.num_occupied_seats().saturating_sub(1), table_layout.ai_profiles_summary());
 
-        let mut balance_history_interactive: Vec<f64> = vec![your_player_balance];
+        let mut balance_history_interactive: Vec<f64> =
+            resumed_balance_history.unwrap_or_else(|| vec![your_player_balance.as_f64()]);
+        let mut balance_events_interactive: Vec<(usize, BalanceEvent)> = resumed_balance_events;
 
         loop {
-            println!("\n--- New Interactive Hand | Your Bal: ${:.2} ---", your_player_balance);
-            log::info!("Starting New Interactive Hand. Your Balance: ${:.2}", your_player_balance);
+            println!("\n--- New Interactive Hand | Your Bal: ${} ---", your_player_balance);
+            log::info!("Starting New Interactive Hand. Your Balance: ${}", your_player_balance);
 
             if your_player_balance < config::MIN_BET_ALLOWED {
-                let msg = format!("\nYour balance (${:.2}) is too low. Game over!", your_player_balance);
+                let msg = format!("\nYour balance (${}) is too low. Game over!", your_player_balance);
                 println!("{}", msg); log::info!("{}", msg);
                 break;
             }
-            if your_player_balance < config::DEFAULT_BET && your_player_balance >= config::MIN_BET_ALLOWED {
-                println!("\nNotice: Your balance (${:.2}) is less than the default bet (${:.2}).", your_player_balance, config::DEFAULT_BET);
+            if (config::MIN_BET_ALLOWED..config::settings().default_bet).contains(&your_player_balance) {
+                println!("\nNotice: Your balance (${}) is less than the default bet (${}).", your_player_balance, config::settings().default_bet);
             }
 
-            if deck.needs_reshuffle(config::RESHUFFLE_THRESHOLD_RATIO) {
+            let mut reshuffled = false;
+            if deck.needs_reshuffle(config::settings().reshuffle_threshold_ratio) {
                 println!("--- Shoe penetration low ({} cards left). Reshuffling... ---", deck.len());
                 log::info!("Reshuffling shoe. Cards left: {}", deck.len());
-                deck = Deck::new(config::NUM_DECKS);
+                deck.reshuffle();
+                session_stats.swings.start_new_shoe();
                 println!("--- New shoe shuffled with {} cards. ---", deck.initial_size);
                 utils::sleep_ms(utils::get_delay_multiplied(500, false));
+                reshuffled = true;
             }
 
-            let mut all_players_at_table: Vec<Player> = Vec::new();
-            all_players_at_table.push(Player::new_user(0, "Your".to_string(), 0.0));
-            for p_id in 1..config::NUM_PLAYERS {
-                all_players_at_table.push(Player::new_ai(p_id, format!("Player {}", p_id + 1)));
-            }
+            deck.start_round();
+            let mut all_players_at_table: Vec<Player> = table_layout.build_players();
             let mut dealer = Dealer::new();
+            let shoe_at_deal = ShoePosition::of(&deck);
+            let balance_before_round = your_player_balance;
 
             if !play_blackjack_round( // Use directly
                 &mut deck,
@@ -181,28 +431,60 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 &mut your_player_balance,
                 &mut session_stats,
                 false, // is_simulation_round = false
-                run_timestamp,
-                1.0, // delay_multiplier
+                session_run_id,
             ) {
                 let msg = "Could not play Your hand (likely insufficient funds). Game over.";
                 println!("{}", msg); log::info!("{}", msg);
                 break;
             }
-
-            balance_history_interactive.push(your_player_balance);
+            write_hand_history(&mut hand_history, RoundRecord::capture(
+                &session_stats, shoe_at_deal, &all_players_at_table, &dealer, balance_before_round, your_player_balance,
+            ));
+
+            balance_history_interactive.push(your_player_balance.as_f64());
+            let round_index = balance_history_interactive.len() - 1;
+            balance_events_interactive.extend(
+                BalanceEvent::of_round(&all_players_at_table, reshuffled, your_player_balance - balance_before_round)
+                    .into_iter().map(|event| (round_index, event)),
+            );
+            record_round_results(&mut session_stats, &all_players_at_table, &dealer, your_player_balance - balance_before_round);
             session_stats.update_balance_extremes(your_player_balance);
-            log::info!("Finished Interactive Hand. Your Balance: ${:.2}", your_player_balance);
-
-            if utils::get_user_input("\nPlay another hand? (y/n): ") != "y" {
+            log::info!("Finished Interactive Hand. Your Balance: ${}", your_player_balance);
+
+            let next_input = utils::get_user_input("\nPlay another hand? (y/n, or (s)ave and quit): ");
+            if next_input == "s" {
+                let saved = SavedSession {
+                    rules: config::rules_summary(),
+                    table: table_layout.describe(),
+                    profile_name: player_profile.as_ref().map(|p| p.name.clone()),
+                    deck: deck.clone(),
+                    your_balance: your_player_balance,
+                    balance_history: balance_history_interactive.clone(),
+                    balance_events: balance_events_interactive.clone(),
+                    session_stats: session_stats.clone(),
+                };
+                let path = session::save_session(&saved)?;
+                println!("Session saved to {}. Choose (r)esume at startup to continue.", path.display());
+                log::info!("Session saved to {}", path.display());
+                session_saved = true;
+                break;
+            }
+            if next_input != "y" {
                 break;
             }
         }
 
-        // Optionally generate graph for interactive mode too
-        // if let Err(e) = generate_balance_graph(&balance_history_interactive, run_timestamp, session_stats.initial_balance) {
-        //     log::error!("Failed to generate balance graph for interactive mode: {}", e);
-        // }
+        // Named after the session's own run id, like its hand history and report, so a resumed session's graph covers all of it
+        if !session_saved {
+            match generate_balance_graph(&balance_history_interactive, &balance_events_interactive, &session_stats.mode,
+                session_stats.run_id, session_stats.initial_balance.as_f64()) {
+                Ok(Some(path)) => graph_paths.push(("Balance".to_string(), path)),
+                Ok(None) => {}
+                Err(e) => log::error!("Failed to generate balance graph for interactive mode: {}", e),
+            }
+        }
 
+        session_balance_history = balance_history_interactive;
         session_stats.final_balance = your_player_balance;
         session_stats.calculate_final_metrics();
 
@@ -211,19 +493,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let final_msg = "Thanks for playing!";
         println!("{}", final_msg); log::info!("{}", final_msg);
 
-        for line in session_stats.to_log_lines() {
-            // Selective printing for interactive console
-            if line.starts_with("Run ID:") || line.starts_with("Mode:") ||
-               line.starts_with("Starting Balance") || line.starts_with("Final Balance") ||
-               line.starts_with("Highest Balance") || line.starts_with("Lowest Balance") ||
-               line.starts_with("Hands Played") || line.starts_with("Your Blackjacks") ||
-               line.starts_with("Your Times Split") || line.starts_with("Configured Default Bet")
-            {
-                 if line.contains("Final balance") && !is_simulation_for_user_player { /* Already shown */ }
-                 else { println!("{}", line); }
-            }
+        for line in session_stats.to_summary_lines() {
+            println!("{}", line);
+        }
+        for line in session_stats.to_log_lines().into_iter().chain(session_stats.ev_matrix.to_log_lines()) {
             log::info!("{}", line);
         }
+        if let Some(trainer_stats) = &session_stats.trainer_stats {
+            for line in trainer_stats.to_log_lines() {
+                println!("{}", line);
+                log::info!("{}", line);
+            }
+        }
+        if let Some(profile) = player_profile.as_mut() {
+            if session_saved {
+                println!("Session saved: profile '{}' will be updated when it is finished.", profile.name);
+            } else if session_stats.practice_mode {
+                println!("Practice session: profile '{}' was not updated.", profile.name);
+            } else {
+                profile.record_session(&session_stats);
+                profile::save_profile(profile)?;
+                for line in profile.to_log_lines() {
+                    println!("{}", line);
+                    log::info!("{}", line);
+                }
+            }
+        }
     }
 
     let script_end_time = Instant::now(); // <<<<---- ADD: Mark script end time
@@ -265,7 +560,119 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // ... (existing interactive results print/log) ...
     }
 
+    // A saved session is reported once it is resumed and finished
+    let mut report_paths = None;
+    let mut html_report_path = None;
+    let mut results_db_path = None;
+    let mut ev_matrix_path = None;
+    if !session_saved {
+        match ev_matrix::write_ev_matrix_csv(&session_stats.ev_matrix, session_stats.run_id) {
+            Ok(path) => ev_matrix_path = Some(path),
+            Err(e) => log::error!("Failed to write EV matrix: {}", e),
+        }
+        let session_report = SessionReport::from_stats(&session_stats);
+        match report::write_session_report(&session_report) {
+            Ok(paths) => report_paths = Some(paths),
+            Err(e) => {
+                log::error!("Failed to write session report: {}", e);
+                eprintln!("Error writing session report: {}", e);
+            }
+        }
+        match report::write_html_report(&session_report, &session_stats.ev_matrix, &graph_paths) {
+            Ok(path) => html_report_path = Some(path),
+            Err(e) => log::error!("Failed to write HTML report: {}", e),
+        }
+        // Rounds come from the hand history, which covers a resumed session from its start
+        let rounds = match &hand_history {
+            Some(_) => history::read_hand_history(&history::hand_history_path(session_stats.run_id)).unwrap_or_else(|e| {
+                log::warn!("Could not read the hand history for the results database: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        match results_db::store_run(&session_report, &rounds) {
+            Ok(path) => results_db_path = Some(path),
+            Err(e) => {
+                log::error!("Failed to store the run in the results database: {}", e);
+                eprintln!("Error storing the run in the results database: {}", e);
+            }
+        }
+        // A resumed session that was saved again has overwritten its save file instead
+        if let Some(path) = &resumed_save_path {
+            if let Err(e) = session::remove_saved_session(path) {
+                log::warn!("Could not remove the finished session's save file {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    if config::settings().terminal_charts {
+        let width = config::TERMINAL_CHART_WIDTH;
+        println!();
+        for line in terminal_chart::balance_chart_lines(&session_balance_history, session_stats.initial_balance.as_f64(), width, config::TERMINAL_CHART_HEIGHT) {
+            println!("{}", line);
+        }
+        println!();
+        for line in terminal_chart::round_result_histogram_lines(&session_balance_history, session_stats.initial_default_bet.as_f64(), width) {
+            println!("{}", line);
+        }
+    }
+
     log::info!("--- Session Ended (RUN ID: {}) ---", run_timestamp);
-    println!("\nFull session results logged to: {}/{}", config::LOGS_DIR_NAME, config::TEXT_LOG_FILENAME);
+    println!("\nFull session results logged to: {}", config::settings().log_file_path(run_timestamp).display());
+    if hand_history.is_some() {
+        println!("Hand history written to: {}", history::hand_history_path(session_stats.run_id).display());
+    }
+    if let Some((json_path, csv_path)) = report_paths {
+        println!("Session report written to: {} (row added to {})", json_path.display(), csv_path.display());
+    }
+    if let Some(path) = html_report_path {
+        println!("HTML report written to: {}", path.display());
+    }
+    if let Some(path) = results_db_path {
+        println!("Run stored in results database: {} (see the runs command)", path.display());
+    }
+    if let Some(path) = ev_matrix_path {
+        println!("EV matrix written to: {}", path.display());
+    }
     Ok(())
 }
+
+// Per-round bookkeeping shared by simulation and interactive play
+fn record_round_results(session_stats: &mut SessionStats, players: &[Player], dealer: &Dealer, your_net_result: money::Money) {
+    session_stats.round_results.add(your_net_result);
+    session_stats.swings.add_round(session_stats.round_results.rounds, your_net_result, session_stats.initial_default_bet);
+    if let (Some(you), Some(&upcard)) = (players.iter().find(|p| p.is_user), dealer.hand.cards.first()) {
+        session_stats.ev_matrix.record_round(&you.hands, upcard);
+    }
+}
+
+// The given file, or asks for one offering the newest hand history in the output directory
+fn resolve_hand_history_file(file: Option<PathBuf>) -> Option<PathBuf> {
+    if file.is_some() {
+        return file;
+    }
+    let latest = history::latest_hand_history_file();
+    let prompt = match &latest {
+        Some(path) => format!("Hand history file (Enter for {}): ", path.display()),
+        None => "Hand history file: ".to_string(),
+    };
+    let input = utils::get_user_input_keep_case(&prompt);
+    if !input.is_empty() {
+        return Some(PathBuf::from(input));
+    }
+    if latest.is_none() {
+        println!("No hand history found in '{}/'.", config::settings().output_dir);
+    }
+    latest
+}
+
+// A failed write is logged and stops further hand history rather than ending the session
+fn write_hand_history(hand_history: &mut Option<HandHistoryWriter>, record: RoundRecord) {
+    if let Some(writer) = hand_history.as_mut() {
+        if let Err(e) = writer.write_round(&record) {
+            log::error!("Failed to write hand history: {}", e);
+            eprintln!("Failed to write hand history: {}. Hand history stopped.", e);
+            *hand_history = None;
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// src/money.rs
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+// An amount of money in integer cents, so balances never drift the way
+// repeated f64 additions do over millions of hands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub struct Money(i64); // Serialized as plain cents
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    pub const fn from_dollars(dollars: i64) -> Self {
+        Money(dollars * 100)
+    }
+
+    // Only for display, graphs and averages; never feed the result back into a balance
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    // Parses user input like "25", "12.5" or "$7.50". More than two decimals is rejected.
+    pub fn parse(input: &str) -> Option<Money> {
+        let trimmed = input.trim().trim_start_matches('$');
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let (whole_str, frac_str) = match digits.split_once('.') {
+            Some((w, f)) => (w, f),
+            None => (digits, ""),
+        };
+        if (whole_str.is_empty() && frac_str.is_empty()) || frac_str.len() > 2 {
+            return None;
+        }
+        if !whole_str.chars().all(|c| c.is_ascii_digit()) || !frac_str.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let whole: i64 = if whole_str.is_empty() { 0 } else { whole_str.parse().ok()? };
+        let frac: i64 = match frac_str.len() {
+            0 => 0,
+            1 => frac_str.parse::<i64>().ok()? * 10,
+            _ => frac_str.parse().ok()?,
+        };
+        let cents = whole.checked_mul(100)?.checked_add(frac)?;
+        Some(Money(if negative { -cents } else { cents }))
+    }
+
+    // Pays `numerator:denominator` on this amount (e.g. 3:2 or 6:5 for a blackjack).
+    // Rounding rule: the exact payout is rounded DOWN to a multiple of `rounding_unit`,
+    // the way a casino pays only in chips it has (a 3:2 payout on $5 with $1 chips is $7).
+    pub fn ratio_payout(self, numerator: i64, denominator: i64, rounding_unit: Money) -> Money {
+        let exact_cents = self.0 * numerator / denominator; // Integer division rounds toward zero
+        if rounding_unit.0 <= 0 {
+            return Money(exact_cents);
+        }
+        Money(exact_cents - exact_cents.rem_euclid(rounding_unit.0))
+    }
+
+    pub fn is_multiple_of(self, unit: Money) -> bool {
+        unit.0 > 0 && self.0 % unit.0 == 0
+    }
+
+    pub fn abs(self) -> Money {
+        Money(self.0.abs())
+    }
+}
+
+// Unit that bets must be a multiple of and payouts are rounded down to
+pub fn smallest_chip(denominations: &[Money]) -> Money {
+    denominations.iter().copied().min().unwrap_or(Money::from_cents(1))
+}
+
+// Formats as dollars with exactly two decimals, e.g. "1000.00"; honours `{:+}` for signed P/L
+// and width, fill and alignment like a number (`{:>10}`, `{:08}`). Precision is ignored.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let abs_cents = self.0.unsigned_abs();
+        f.pad_integral(self.0 >= 0, "", &format!("{}.{:02}", abs_cents / 100, abs_cents % 100))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+    fn mul(self, rhs: i64) -> Money {
+        Money(self.0 * rhs)
+    }
+}
+
+// Rounds toward zero, to the cent
+impl Div<i64> for Money {
+    type Output = Money;
+    fn div(self, rhs: i64) -> Money {
+        Money(self.0 / rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_dollars_and_cents() {
+        assert_eq!(Money::parse("25"), Some(Money::from_cents(2500)));
+        assert_eq!(Money::parse("12.5"), Some(Money::from_cents(1250)));
+        assert_eq!(Money::parse("$7.50"), Some(Money::from_cents(750)));
+        assert_eq!(Money::parse(" 0.05 "), Some(Money::from_cents(5)));
+        assert_eq!(Money::parse(".5"), Some(Money::from_cents(50)));
+        assert_eq!(Money::parse("3."), Some(Money::from_cents(300)));
+    }
+
+    #[test]
+    fn parse_accepts_negative_amounts() {
+        assert_eq!(Money::parse("-3.25"), Some(Money::from_cents(-325)));
+        assert_eq!(Money::parse("$-5"), Some(Money::from_cents(-500)));
+        assert_eq!(Money::parse("-0.01"), Some(Money::from_cents(-1)));
+    }
+
+    #[test]
+    fn parse_rejects_fractional_cents() {
+        assert_eq!(Money::parse("1.234"), None);
+        assert_eq!(Money::parse("0.001"), None);
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        for input in ["", " ", "$", "-", ".", "abc", "12a", "1.2.3", "1e3", "+5", "--5", "-$5", "1,000", "1.-5", "99999999999999999999"] {
+            assert_eq!(Money::parse(input), None, "{:?} should not parse", input);
+        }
+    }
+
+    #[test]
+    fn three_to_two_rounds_down_to_the_chip() {
+        let chip = Money::from_dollars(1);
+        assert_eq!(Money::from_dollars(10).ratio_payout(3, 2, chip), Money::from_dollars(15));
+        assert_eq!(Money::from_dollars(5).ratio_payout(3, 2, chip), Money::from_dollars(7)); // Exact: 7.50
+        assert_eq!(Money::from_dollars(25).ratio_payout(3, 2, chip), Money::from_dollars(37)); // Exact: 37.50
+        assert_eq!(Money::from_dollars(25).ratio_payout(3, 2, Money::from_dollars(5)), Money::from_dollars(35));
+        assert_eq!(Money::from_dollars(5).ratio_payout(3, 2, Money::from_cents(1)), Money::from_cents(750));
+    }
+
+    #[test]
+    fn six_to_five_rounds_down_to_the_chip() {
+        let chip = Money::from_dollars(1);
+        assert_eq!(Money::from_dollars(25).ratio_payout(6, 5, chip), Money::from_dollars(30));
+        assert_eq!(Money::from_dollars(7).ratio_payout(6, 5, chip), Money::from_dollars(8)); // Exact: 8.40
+        assert_eq!(Money::from_dollars(13).ratio_payout(6, 5, chip), Money::from_dollars(15)); // Exact: 15.60
+        assert_eq!(Money::from_cents(1).ratio_payout(6, 5, Money::from_cents(1)), Money::from_cents(1)); // Exact: 1.2 cents
+    }
+
+    #[test]
+    fn payout_without_a_rounding_unit_is_exact_to_the_cent() {
+        assert_eq!(Money::from_dollars(5).ratio_payout(3, 2, Money::ZERO), Money::from_cents(750));
+        assert_eq!(Money::from_cents(333).ratio_payout(6, 5, Money::ZERO), Money::from_cents(399)); // 399.6 cents
+    }
+
+    #[test]
+    fn display_honours_sign_width_and_alignment() {
+        assert_eq!(Money::from_cents(123456).to_string(), "1234.56");
+        assert_eq!(Money::from_cents(-5).to_string(), "-0.05");
+        assert_eq!(format!("{:+}", Money::from_dollars(5)), "+5.00");
+        assert_eq!(format!("{:+}", Money::from_dollars(-5)), "-5.00");
+        assert_eq!(format!("{:>10}|", Money::from_cents(123456)), "   1234.56|");
+        assert_eq!(format!("{:<10}|", Money::from_cents(-250)), "-2.50     |");
+        assert_eq!(format!("{:^9}|", Money::from_dollars(1)), "  1.00   |");
+        assert_eq!(format!("{:+08}", Money::from_dollars(3)), "+0003.00");
+    }
+}
@@ -1,11 +1,15 @@
 // src/player.rs
 use crate::hand::Hand; // Assuming hand.rs is in the same crate (src/)
+use crate::config;
+use crate::money::Money;
+use crate::strategy::AiProfile;
 
 #[derive(Debug, Clone)]
 pub struct Player {
-    pub id: usize, // 0 for "You", 1+ for AI
+    pub seat: usize, // Table seat index, 0 = first base
     pub name: String,
     pub is_user: bool,
+    pub ai_profile: AiProfile, // How an AI seat plays; always Book for "You"
     pub hands: Vec<Hand>,
     // Flags for "Your" player to track if a split/double happened in the *current round's initial hand*
     // This helps avoid over-counting in stats['hands_involved_in_split/double']
@@ -14,23 +18,25 @@ pub struct Player {
 }
 
 impl Player {
-    pub fn new_user(id: usize, name: String, initial_bet: f64) -> Self {
+    pub fn new_user(seat: usize, name: String, initial_bet: Money) -> Self {
         Player {
-            id,
+            seat,
             name,
             is_user: true,
+            ai_profile: AiProfile::Book,
             hands: vec![Hand::new(initial_bet)],
             hand_involved_in_split_this_round: false,
             hand_involved_in_double_this_round: false,
         }
     }
 
-    pub fn new_ai(id: usize, name: String) -> Self {
+    pub fn new_ai(seat: usize, name: String, ai_profile: AiProfile) -> Self {
         Player {
-            id,
+            seat,
             name,
             is_user: false,
-            hands: vec![Hand::new(0.0)], // AI bet is not financially tracked
+            ai_profile,
+            hands: vec![Hand::new(Money::ZERO)], // AI bet is not financially tracked
             hand_involved_in_split_this_round: false, // Not relevant for AI stat tracking
             hand_involved_in_double_this_round: false, // Not relevant for AI stat tracking
         }
@@ -44,6 +50,111 @@ impl Player {
     }
 }
 
+// Which seats are occupied and where "You" sit. Players are dealt in seat order,
+// first base (seat 0) to third base (num_seats - 1), like a real table.
+#[derive(Debug, Clone)]
+pub struct TableLayout {
+    pub num_seats: usize,
+    pub your_seat: usize,
+    pub empty_seats: Vec<usize>,
+    pub ai_profiles: Vec<(usize, AiProfile)>,
+}
+
+impl TableLayout {
+    pub fn from_config() -> Self {
+        TableLayout {
+            num_seats: config::settings().num_players,
+            your_seat: config::settings().your_seat_index,
+            empty_seats: config::settings().empty_seats.clone(),
+            ai_profiles: config::settings().ai_seat_profiles.clone(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_seats == 0 || self.num_seats > config::MAX_SEATS {
+            return Err(format!("Seat count must be between 1 and {} (got {}).", config::MAX_SEATS, self.num_seats));
+        }
+        if self.your_seat >= self.num_seats {
+            return Err(format!("Your seat {} is outside the table (seats 1-{}).", self.your_seat + 1, self.num_seats));
+        }
+        if let Some(bad_seat) = self.empty_seats.iter().find(|&&s| s >= self.num_seats) {
+            return Err(format!("Empty seat {} is outside the table (seats 1-{}).", bad_seat + 1, self.num_seats));
+        }
+        if self.empty_seats.contains(&self.your_seat) {
+            return Err(format!("Your seat {} cannot also be marked empty.", self.your_seat + 1));
+        }
+        for (i, &(seat, profile)) in self.ai_profiles.iter().enumerate() {
+            if self.ai_profiles[..i].iter().any(|&(s, _)| s == seat) {
+                return Err(format!("Seat {} has more than one AI profile.", seat + 1));
+            }
+            if seat >= self.num_seats || seat == self.your_seat || self.empty_seats.contains(&seat) {
+                return Err(format!("AI profile '{}' assigned to seat {}, which is not an occupied AI seat.", profile, seat + 1));
+            }
+            if let AiProfile::Sloppy { error_rate } = profile {
+                if !(0.0..=1.0).contains(&error_rate) {
+                    return Err(format!("Error rate for seat {} must be between 0 and 1 (got {}).", seat + 1, error_rate));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn num_occupied_seats(&self) -> usize {
+        (0..self.num_seats).filter(|s| !self.empty_seats.contains(s)).count()
+    }
+
+    pub fn seat_label(&self, seat: usize) -> String {
+        if self.num_seats > 1 && seat == 0 {
+            format!("Seat {} (first base)", seat + 1)
+        } else if self.num_seats > 1 && seat == self.num_seats - 1 {
+            format!("Seat {} (third base)", seat + 1)
+        } else {
+            format!("Seat {}", seat + 1)
+        }
+    }
+
+    pub fn ai_profile_for_seat(&self, seat: usize) -> AiProfile {
+        self.ai_profiles.iter()
+            .find(|(s, _)| *s == seat)
+            .map(|&(_, profile)| profile)
+            .unwrap_or(AiProfile::Book)
+    }
+
+    pub fn all_ai_play_book(&self) -> bool {
+        self.ai_profiles.iter().all(|&(_, profile)| profile == AiProfile::Book)
+    }
+
+    // Seat layout and AI behaviour in one line; a saved session only resumes at the same table
+    pub fn describe(&self) -> String {
+        format!("{} seats, You at Seat {}, AI: {}", self.num_seats, self.your_seat + 1, self.ai_profiles_summary())
+    }
+
+    // e.g. "Seat 2: Book, Seat 3: Never Bust"
+    pub fn ai_profiles_summary(&self) -> String {
+        let parts: Vec<String> = (0..self.num_seats)
+            .filter(|s| *s != self.your_seat && !self.empty_seats.contains(s))
+            .map(|s| format!("Seat {}: {}", s + 1, self.ai_profile_for_seat(s)))
+            .collect();
+        if parts.is_empty() { "None".to_string() } else { parts.join(", ") }
+    }
+
+    // Occupied seats only, in dealing order
+    pub fn build_players(&self) -> Vec<Player> {
+        let mut players = Vec::new();
+        for seat in 0..self.num_seats {
+            if self.empty_seats.contains(&seat) {
+                continue;
+            }
+            if seat == self.your_seat {
+                players.push(Player::new_user(seat, "Your".to_string(), Money::ZERO));
+            } else {
+                players.push(Player::new_ai(seat, format!("Player {}", seat + 1), self.ai_profile_for_seat(seat)));
+            }
+        }
+        players
+    }
+}
+
 // Dealer can be a simplified Player-like struct or its own distinct struct
 #[derive(Debug, Clone)]
 pub struct Dealer {
@@ -53,7 +164,7 @@ pub struct Dealer {
 impl Dealer {
     pub fn new() -> Self {
         Dealer {
-            hand: Hand::new(0.0), // Bet is irrelevant
+            hand: Hand::new(Money::ZERO), // Bet is irrelevant
         }
     }
 }
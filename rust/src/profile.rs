@@ -0,0 +1,313 @@
+// src/profile.rs
+use crate::cli::ProfileArgs;
+use crate::config;
+use crate::drill::{cell_label, DrillCell};
+use crate::money::Money;
+use crate::stats::SessionStats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccuracyRecord {
+    pub run_id: u64,
+    pub source: String, // "Trainer" or a drill name
+    pub decisions: u32,
+    pub correct: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleSetResults {
+    pub sessions: u32,
+    pub hands_played: u32,
+    pub net_profit_loss: Money,
+}
+
+// A named player persisted as profiles/<name>.json; carries the balance between sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub balance: Money,
+    pub sessions_played: u32,
+    pub hands_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub net_profit_loss: Money,
+    pub accuracy_history: Vec<AccuracyRecord>,
+    pub results_by_rules: BTreeMap<String, RuleSetResults>, // Keyed by config::rules_summary()
+    // Strategy drill misses per chart cell, over all drills; the next drill is weighted toward them
+    #[serde(default, with = "drill_miss_entries")]
+    pub drill_misses: BTreeMap<DrillCell, u32>,
+}
+
+// JSON object keys must be strings, so the per-cell map is saved as a list of entries
+mod drill_miss_entries {
+    use crate::drill::DrillCell;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(misses: &BTreeMap<DrillCell, u32>, serializer: S) -> Result<S::Ok, S::Error> {
+        misses.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<DrillCell, u32>, D::Error> {
+        Ok(Vec::<(DrillCell, u32)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+impl PlayerProfile {
+    pub fn new(name: &str) -> Self {
+        PlayerProfile {
+            name: name.to_string(),
+            balance: config::settings().starting_balance,
+            sessions_played: 0,
+            hands_played: 0,
+            wins: 0,
+            losses: 0,
+            pushes: 0,
+            blackjacks: 0,
+            net_profit_loss: Money::ZERO,
+            accuracy_history: Vec::new(),
+            results_by_rules: BTreeMap::new(),
+            drill_misses: BTreeMap::new(),
+        }
+    }
+
+    // Adds a finished (non-practice) session to the lifetime totals
+    pub fn record_session(&mut self, session_stats: &SessionStats) {
+        self.balance = session_stats.final_balance;
+        self.sessions_played += 1;
+        self.hands_played += session_stats.hands_played_session;
+        self.wins += session_stats.total_wins;
+        self.losses += session_stats.total_losses;
+        self.pushes += session_stats.total_pushes;
+        self.blackjacks += session_stats.blackjacks_dealt_player;
+        self.net_profit_loss += session_stats.net_profit_loss;
+
+        let rule_set = self.results_by_rules.entry(config::rules_summary()).or_default();
+        rule_set.sessions += 1;
+        rule_set.hands_played += session_stats.hands_played_session;
+        rule_set.net_profit_loss += session_stats.net_profit_loss;
+
+        if let Some(trainer_stats) = &session_stats.trainer_stats {
+            if trainer_stats.total_decisions() > 0 {
+                self.record_accuracy(session_stats.run_id, "Trainer", trainer_stats.total_decisions(), trainer_stats.total_correct());
+            }
+        }
+    }
+
+    pub fn record_accuracy(&mut self, run_id: u64, source: &str, decisions: u32, correct: u32) {
+        self.accuracy_history.push(AccuracyRecord { run_id, source: source.to_string(), decisions, correct });
+    }
+
+    pub fn record_drill_misses(&mut self, misses_per_cell: &BTreeMap<DrillCell, u32>) {
+        for (&cell, &misses) in misses_per_cell {
+            *self.drill_misses.entry(cell).or_insert(0) += misses;
+        }
+    }
+
+    pub fn lifetime_accuracy_pct(&self) -> Option<f64> {
+        let decisions: u32 = self.accuracy_history.iter().map(|r| r.decisions).sum();
+        let correct: u32 = self.accuracy_history.iter().map(|r| r.correct).sum();
+        if decisions > 0 { Some(correct as f64 / decisions as f64 * 100.0) } else { None }
+    }
+
+    pub fn summary_line(&self) -> String {
+        let accuracy = match self.lifetime_accuracy_pct() {
+            Some(pct) => format!("{:.1}%", pct),
+            None => "n/a".to_string(),
+        };
+        format!("{}: Balance ${}, Sessions {}, Hands {}, W/L/P {}/{}/{}, Net ${:+}, Accuracy {}",
+            self.name, self.balance, self.sessions_played, self.hands_played,
+            self.wins, self.losses, self.pushes, self.net_profit_loss, accuracy)
+    }
+
+    pub fn to_log_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Profile {}", self.summary_line())];
+        for (rules, results) in &self.results_by_rules {
+            lines.push(format!("  Rules [{}]: Sessions {}, Hands {}, Net ${:+}",
+                rules, results.sessions, results.hands_played, results.net_profit_loss));
+        }
+        let mut most_missed: Vec<(&DrillCell, &u32)> = self.drill_misses.iter().collect();
+        most_missed.sort_by(|a, b| b.1.cmp(a.1));
+        if !most_missed.is_empty() {
+            let cells: Vec<String> = most_missed.into_iter().take(5)
+                .map(|(&cell, misses)| format!("{} ({})", cell_label(cell), misses))
+                .collect();
+            lines.push(format!("  Most Missed Drill Cells: {}", cells.join(", ")));
+        }
+        lines
+    }
+}
+
+fn profiles_dir() -> PathBuf {
+    PathBuf::from(config::PROFILES_DIR_NAME)
+}
+
+// Names typed at a prompt are lowercased by get_user_input, so names from the command line
+// are too: "--profile Alice" and "alice" at the prompt open the same profile
+pub fn normalize_profile_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+// Profile names become file names, so keep them to letters, digits, '-' and '_'
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 40 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn profile_path(name: &str) -> PathBuf {
+    profiles_dir().join(format!("{}.json", name))
+}
+
+pub fn load_profile(name: &str) -> Result<Option<PlayerProfile>, Box<dyn std::error::Error>> {
+    let path = profile_path(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+pub fn save_profile(profile: &PlayerProfile) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(profiles_dir())?;
+    fs::write(profile_path(&profile.name), serde_json::to_string_pretty(profile)?)?;
+    Ok(())
+}
+
+pub fn delete_profile(name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let path = profile_path(name);
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(path)?;
+    Ok(true)
+}
+
+// Normalizes and checks a name given for an existing profile
+fn existing_profile_name(name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let name = normalize_profile_name(name);
+    if !is_valid_profile_name(&name) {
+        return Err(format!("Invalid profile name '{}'.", name).into());
+    }
+    if load_profile(&name)?.is_none() {
+        return Err(format!("No profile named '{}'.", name).into());
+    }
+    Ok(name)
+}
+
+pub fn print_profiles() -> Result<(), Box<dyn std::error::Error>> {
+    let profiles = list_profiles()?;
+    if profiles.is_empty() {
+        println!("No profiles saved in '{}/'.", config::PROFILES_DIR_NAME);
+    }
+    for profile in profiles {
+        for line in profile.to_log_lines() {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+// Starts the profile over: starting balance, no stats
+pub fn reset_profile(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let name = existing_profile_name(name)?;
+    save_profile(&PlayerProfile::new(&name))?;
+    println!("Profile '{}' reset to a balance of ${}.", name, config::settings().starting_balance);
+    log::info!("Profile '{}' reset.", name);
+    Ok(())
+}
+
+pub fn remove_profile(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let name = existing_profile_name(name)?;
+    delete_profile(&name)?;
+    println!("Profile '{}' deleted.", name);
+    log::info!("Profile '{}' deleted.", name);
+    Ok(())
+}
+
+pub fn list_profiles() -> Result<Vec<PlayerProfile>, Box<dyn std::error::Error>> {
+    let dir = profiles_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        match fs::read_to_string(&path).map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str::<PlayerProfile>(&contents).map_err(|e| e.to_string())) {
+            Ok(profile) => profiles.push(profile),
+            Err(e) => log::warn!("Skipping unreadable profile {:?}: {}", path, e),
+        }
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+// Loads the named profile, creating it if new
+pub fn open_profile(name: &str) -> Result<PlayerProfile, Box<dyn std::error::Error>> {
+    let name = normalize_profile_name(name);
+    if !is_valid_profile_name(&name) {
+        return Err(format!("Invalid profile name '{}': use letters, digits, '-' and '_' (max 40).", name).into());
+    }
+    let mut profile = match load_profile(&name)? {
+        Some(existing) => {
+            println!("Welcome back! {}", existing.summary_line());
+            existing
+        }
+        None => {
+            println!("Created new profile '{}' with ${}.", name, config::settings().starting_balance);
+            PlayerProfile::new(&name)
+        }
+    };
+    if profile.balance < config::MIN_BET_ALLOWED &&
+       crate::utils::get_user_input("Profile balance is below the minimum bet. Reset it? (y/n): ") == "y" {
+        profile.balance = config::settings().starting_balance;
+    }
+    Ok(profile)
+}
+
+// Uses --profile / --no-profile when given, otherwise asks. None plays without a profile.
+pub fn profile_from_args(args: &ProfileArgs) -> Result<Option<PlayerProfile>, Box<dyn std::error::Error>> {
+    if args.no_profile {
+        return Ok(None);
+    }
+    if let Some(name) = &args.profile {
+        return Ok(Some(open_profile(name)?));
+    }
+    loop {
+        let name = crate::utils::get_user_input("Profile name (or press Enter to play without a profile): ");
+        if name.is_empty() {
+            return Ok(None);
+        }
+        if !is_valid_profile_name(&name) {
+            println!("Profile names may only use letters, digits, '-' and '_' (max 40).");
+            continue;
+        }
+        return Ok(Some(open_profile(&name)?));
+    }
+}
+
+// Interactive (l)ist / (r)eset / (d)elete menu for profiles
+pub fn run_profile_menu() -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let choice = crate::utils::get_user_input("Profiles: (l)ist, (r)eset, (d)elete, or (q)uit? ");
+        match choice.as_str() {
+            "l" => print_profiles()?,
+            "r" | "d" => {
+                let name = crate::utils::get_user_input("Profile name: ");
+                let result = if choice == "r" { reset_profile(&name) } else { remove_profile(&name) };
+                if let Err(e) = result {
+                    println!("{}", e);
+                }
+            }
+            "q" | "" => return Ok(()),
+            _ => println!("Invalid choice."),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// src/replay.rs
+use crate::card_deck::Card;
+use crate::config;
+use crate::hand::{DecisionSource, HandEvent, HandStatus};
+use crate::history::{read_hand_history, RoundRecord};
+use crate::money::Money;
+use crate::player::{Dealer, Player};
+use crate::strategy::{calculate_value_for_strategy, get_basic_strategy_action, restrict_to_allowed_action, PlayerAction};
+use crate::utils;
+use std::path::Path;
+
+// One of "Your" recorded decisions next to what the Book would have played there
+struct ReviewedDecision {
+    hand_number: usize, // 1-based
+    cards: Vec<Card>,
+    action: PlayerAction,
+    source: DecisionSource,
+    book: PlayerAction,
+}
+
+impl ReviewedDecision {
+    fn is_mistake(&self) -> bool {
+        self.source == DecisionSource::User && self.action != self.book
+    }
+}
+
+struct ReviewedRound {
+    record: RoundRecord,
+    decisions: Vec<ReviewedDecision>,
+}
+
+impl ReviewedRound {
+    fn new(record: RoundRecord) -> Self {
+        let decisions = review_decisions(&record);
+        ReviewedRound { record, decisions }
+    }
+
+    fn has_mistake(&self) -> bool {
+        self.decisions.iter().any(|d| d.is_mistake())
+    }
+
+    fn net_change(&self) -> Money {
+        self.record.balance_after - self.record.balance_before
+    }
+
+    // Measured in the bet "You" placed that round, not today's default bet
+    fn is_large_swing(&self) -> bool {
+        let your_bet = self.record.seats.iter().find(|s| s.is_user)
+            .and_then(|you| you.hands.first())
+            .map_or(Money::ZERO, |hand| hand.original_bet());
+        your_bet > Money::ZERO && self.net_change().abs() >= your_bet * config::REPLAY_LARGE_SWING_BETS
+    }
+}
+
+// Walks "Your" hand events, rebuilding the cards held at each decision. Hands are played
+// in order, so the hand count at a decision is one plus the splits made before it.
+// Double and split are offered as in the game: only when the balance covers the hand's bet.
+fn review_decisions(record: &RoundRecord) -> Vec<ReviewedDecision> {
+    let (Some(you), Some(upcard)) = (record.seats.iter().find(|s| s.is_user), record.dealer_cards.first()) else {
+        return Vec::new();
+    };
+    let upcard_value = upcard.rank.value().0;
+    let mut num_hands = 1;
+    let mut decisions = Vec::new();
+    for (hand_idx, hand) in you.hands.iter().enumerate() {
+        let mut cards: Vec<Card> = Vec::new();
+        for event in &hand.events {
+            let (action, source) = match event {
+                HandEvent::Dealt(card) | HandEvent::SplitFrom { card, .. } => { cards.push(*card); continue; }
+                HandEvent::Hit(source) => (PlayerAction::Hit, *source),
+                HandEvent::Stand(source) => (PlayerAction::Stand, *source),
+                HandEvent::Double(source) => (PlayerAction::Double, *source),
+                HandEvent::Split(source) => (PlayerAction::Split, *source),
+                _ => continue,
+            };
+            let can_afford = record.balance_before >= hand.original_bet();
+            let can_double = cards.len() == 2 && can_afford;
+            let can_split = cards.len() == 2 && cards[0].rank.blackjack_value() == cards[1].rank.blackjack_value()
+                && num_hands < 4 && can_afford;
+            let book = restrict_to_allowed_action(
+                get_basic_strategy_action(&cards, upcard_value, num_hands, false),
+                &cards, upcard_value, can_double, can_split, false,
+            );
+            decisions.push(ReviewedDecision { hand_number: hand_idx + 1, cards: cards.clone(), action, source, book });
+            if action == PlayerAction::Split {
+                cards.pop(); // The second card moved to the new hand
+                num_hands += 1;
+            }
+        }
+    }
+    decisions
+}
+
+fn display_round(round: &ReviewedRound, position: usize, num_rounds: usize) {
+    let record = &round.record;
+    println!("\n=== Round {} ({} of {}) | Run {} | Shoe: {} of {} cards left ===",
+        record.round, position + 1, num_rounds, record.run_id, record.shoe.cards_remaining, record.shoe.shoe_size);
+
+    let mut dealer = Dealer::new();
+    dealer.hand.cards = record.dealer_cards.clone();
+    dealer.hand.status = if record.dealer_value > 21 { HandStatus::Busted } else { HandStatus::Stood };
+    for seat in &record.seats {
+        if seat.is_user {
+            let mut you = Player::new_user(seat.seat - 1, seat.name.clone(), Money::ZERO);
+            you.hands = seat.hands.iter().map(|h| h.to_hand()).collect();
+            utils::display_your_hands_and_dealer(&you, &dealer, false);
+        } else {
+            for (i, hand) in seat.hands.iter().enumerate() {
+                let cards_str: Vec<String> = hand.cards.iter().map(|c| c.to_string()).collect();
+                println!("{} (AI, Seat {}, {}) Hand {}: {} (Value: {}) [{}]", seat.name, seat.seat,
+                    seat.ai_profile.as_deref().unwrap_or("Book"), i + 1, cards_str.join(", "),
+                    calculate_value_for_strategy(&hand.cards), hand.status);
+            }
+        }
+    }
+
+    if round.decisions.is_empty() {
+        println!("No decisions for You this round.");
+    }
+    for decision in &round.decisions {
+        let cards_str: Vec<String> = decision.cards.iter().map(|c| c.to_string()).collect();
+        let situation = format!("Your Hand {}: {} ({}) vs {}", decision.hand_number, cards_str.join(" "),
+            calculate_value_for_strategy(&decision.cards), record.dealer_cards[0]);
+        if decision.source != DecisionSource::User {
+            println!("  {} - {}: {}", situation, decision.source, decision.action);
+        } else if decision.is_mistake() {
+            println!("  {} - You: {}, Book: {}  <-- MISTAKE", situation, decision.action, decision.book);
+        } else {
+            println!("  {} - You: {} (matches Book)", situation, decision.action);
+        }
+    }
+    println!("Result: ${:+} | Balance ${} -> ${}{}", round.net_change(), record.balance_before, record.balance_after,
+        if round.is_large_swing() { "  (large swing)" } else { "" });
+}
+
+// Steps through an exported hand history; can jump to rounds with mistakes or large swings
+pub fn run_replay(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let rounds: Vec<ReviewedRound> = read_hand_history(path)?.into_iter().map(ReviewedRound::new).collect();
+    if rounds.is_empty() {
+        println!("No rounds in {}.", path.display());
+        return Ok(());
+    }
+    let mistakes = rounds.iter().filter(|r| r.has_mistake()).count();
+    let swings = rounds.iter().filter(|r| r.is_large_swing()).count();
+    let summary = format!("Replaying {}: {} rounds, {} with mistakes, {} large swings (>= {} bets)",
+        path.display(), rounds.len(), mistakes, swings, config::REPLAY_LARGE_SWING_BETS);
+    println!("{}", summary);
+    log::info!("{}", summary);
+
+    let mut position = 0;
+    display_round(&rounds[position], position, rounds.len());
+    loop {
+        let choice = utils::get_user_input(
+            "\n(n)ext, (p)revious, next (m)istake, next large (s)wing, (g)oto round, or (q)uit: ",
+        );
+        let target = match choice.as_str() {
+            "n" | "" => (position + 1 < rounds.len()).then_some(position + 1),
+            "p" => position.checked_sub(1),
+            "m" => (position + 1..rounds.len()).find(|&i| rounds[i].has_mistake()),
+            "s" => (position + 1..rounds.len()).find(|&i| rounds[i].is_large_swing()),
+            "g" => {
+                let round_no = utils::get_user_input("Round number: ").parse::<u32>().unwrap_or(0);
+                rounds.iter().position(|r| r.record.round == round_no)
+            }
+            "q" => return Ok(()),
+            _ => { println!("Invalid choice."); continue; }
+        };
+        match target {
+            Some(new_position) => {
+                position = new_position;
+                display_round(&rounds[position], position, rounds.len());
+            }
+            None => println!("No such round from here."),
+        }
+    }
+}
+
+// Non-interactive summary of an exported hand history, printed and logged
+pub fn run_analyze(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let rounds: Vec<ReviewedRound> = read_hand_history(path)?.into_iter().map(ReviewedRound::new).collect();
+    if rounds.is_empty() {
+        println!("No rounds in {}.", path.display());
+        return Ok(());
+    }
+    let your_hands = rounds.iter()
+        .filter_map(|r| r.record.seats.iter().find(|s| s.is_user))
+        .flat_map(|s| s.hands.iter());
+    let (mut wins, mut losses, mut pushes) = (0, 0, 0);
+    for hand in your_hands {
+        if hand.net_result > Money::ZERO { wins += 1; }
+        else if hand.net_result < Money::ZERO { losses += 1; }
+        else { pushes += 1; }
+    }
+    let decisions: Vec<&ReviewedDecision> = rounds.iter().flat_map(|r| r.decisions.iter()).collect();
+    let count_source = |source: DecisionSource| decisions.iter().filter(|d| d.source == source).count();
+    let mistakes = decisions.iter().filter(|d| d.is_mistake()).count();
+    let net = rounds.iter().fold(Money::ZERO, |total, r| total + r.net_change());
+    let biggest_win = rounds.iter().max_by_key(|r| r.net_change()).map(|r| r.net_change()).unwrap_or(Money::ZERO);
+    let biggest_loss = rounds.iter().min_by_key(|r| r.net_change()).map(|r| r.net_change()).unwrap_or(Money::ZERO);
+
+    let lines = vec![
+        format!("--- Hand History Analysis: {} ---", path.display()),
+        format!("Rounds: {} (Run {}, {})", rounds.len(), rounds[0].record.run_id, rounds[0].record.mode),
+        format!("Balance: ${} -> ${} (Net ${:+})",
+            rounds[0].record.balance_before, rounds[rounds.len() - 1].record.balance_after, net),
+        format!("Your Hands W/L/P: {}/{}/{}", wins, losses, pushes),
+        format!("Decisions: {} (You {}, Book {}, Index Play {}), Mistakes vs Book: {}", decisions.len(),
+            count_source(DecisionSource::User), count_source(DecisionSource::Book), count_source(DecisionSource::IndexPlay), mistakes),
+        format!("Rounds with Mistakes: {}, Large Swings (>= {} bets): {}",
+            rounds.iter().filter(|r| r.has_mistake()).count(),
+            config::REPLAY_LARGE_SWING_BETS,
+            rounds.iter().filter(|r| r.is_large_swing()).count()),
+        format!("Biggest Win: ${:+}, Biggest Loss: ${:+}", biggest_win, biggest_loss),
+    ];
+    for line in lines {
+        println!("{}", line);
+        log::info!("{}", line);
+    }
+    Ok(())
+}
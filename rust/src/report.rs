@@ -0,0 +1,409 @@
+// src/report.rs
+use crate::config;
+use crate::ev_matrix::{row_label, ChartCell, EvMatrix};
+use crate::graph::ev_color;
+use crate::stats::SessionStats;
+use crate::trainer::upcard_label;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Machine-readable summary of a finished session, for dashboards and scripts.
+// Money amounts are in dollars (the hand history stores cents).
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReport {
+    pub run_id: u64,
+    pub mode: String,
+    pub practice: bool,
+    pub seed: Option<u64>,
+    pub target_iterations: Option<u32>,
+    pub rules: RulesReport,
+    pub counters: CountersReport,
+    pub metrics: MetricsReport,
+    pub confidence: ConfidenceReport,
+    pub swings: SwingsReport,
+    pub timing: TimingReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RulesReport {
+    pub summary: String, // config::rules_summary(), the key used to group results by rule set
+    pub num_decks: usize,
+    pub blackjack_payout: String, // e.g. "6:5"
+    pub reshuffle_threshold_ratio: f64,
+    pub num_players: usize,
+    pub your_seat: usize, // 1 = first base
+    pub ai_seat_profiles: String,
+    pub stacked_shoe_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountersReport {
+    pub hands_played: u32, // "Your" main hands
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub times_split: u32,
+    pub hands_involved_in_split: u32,
+    pub hands_after_splits: u32,
+    pub split_hand_parts_resolved: u32,
+    pub times_doubled: u32,
+    pub hands_involved_in_double: u32,
+    pub doubled_hands_resolved: u32,
+    pub undos_used: u32,
+    pub redos_used: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsReport {
+    pub default_bet: f64,
+    pub initial_balance: f64,
+    pub final_balance: f64,
+    pub highest_balance: f64,
+    pub lowest_balance: f64,
+    pub net_profit_loss: f64,
+    pub avg_profit_loss_per_hand: f64,
+    pub ev_per_initial_bet_pct: f64, // Net result per main hand as a percentage of the default bet
+    pub win_rate_pct: f64,           // Wins over resolved hands (wins + losses + pushes)
+    pub split_net_profit_loss: f64,
+    pub avg_profit_loss_per_split_part: f64,
+    pub doubled_net_profit_loss: f64,
+    pub avg_profit_loss_per_doubled_hand: f64,
+    pub trainer_accuracy_pct: Option<f64>,
+}
+
+// Spread of "Your" per-round results. "bets" are multiples of the default bet; None below two rounds.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfidenceReport {
+    pub rounds: u32,
+    pub variance_per_round_bets: Option<f64>,
+    pub std_dev_per_hand_bets: Option<f64>,
+    pub std_error_bets: Option<f64>,
+    pub ev_ci95_low_pct: Option<f64>,
+    pub ev_ci95_high_pct: Option<f64>,
+    pub target_precision_pct: f64, // Half-width of the 95% interval aimed for
+    pub hands_for_target_precision: Option<u64>,
+}
+
+// "Your" streaks and swings, by round; pushes don't break a streak. Stop thresholds are in default bets.
+#[derive(Debug, Clone, Serialize)]
+pub struct SwingsReport {
+    pub longest_win_streak: u32,
+    pub longest_loss_streak: u32,
+    pub largest_round_win: Option<f64>,
+    pub largest_round_win_round: Option<u32>,
+    pub largest_round_loss: Option<f64>,
+    pub largest_round_loss_round: Option<u32>,
+    pub shoes: usize, // The last one may be partial
+    pub winning_shoes: usize,
+    pub best_shoe: Option<f64>,
+    pub worst_shoe: Option<f64>,
+    pub stop_session_hands: u32,
+    pub stop_win_bets: i64,
+    pub stop_loss_bets: i64,
+    pub stop_sessions: u32,
+    pub stop_win_first: u32,
+    pub stop_loss_first: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimingReport {
+    pub started_at: String, // RFC 3339, from the RUN_ID
+    pub runtime_seconds: f64,
+    pub avg_time_per_hand_seconds: f64,
+    pub hands_per_second: f64,
+}
+
+fn pct(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 { part / whole * 100.0 } else { 0.0 }
+}
+
+impl SessionReport {
+    // Expects calculate_final_metrics() and the timing fields to be up to date
+    pub fn from_stats(stats: &SessionStats) -> Self {
+        let settings = config::settings();
+        let resolved_hands = (stats.total_wins + stats.total_losses + stats.total_pushes) as f64;
+        let hands = stats.hands_played_session as f64;
+        SessionReport {
+            run_id: stats.run_id,
+            mode: stats.mode.clone(),
+            practice: stats.practice_mode,
+            seed: settings.seed,
+            target_iterations: stats.target_iterations,
+            rules: RulesReport {
+                summary: config::rules_summary(),
+                num_decks: settings.num_decks,
+                blackjack_payout: format!("{}:{}", settings.blackjack_payout_numerator, settings.blackjack_payout_denominator),
+                reshuffle_threshold_ratio: settings.reshuffle_threshold_ratio,
+                num_players: settings.num_players,
+                your_seat: settings.your_seat_index + 1,
+                ai_seat_profiles: stats.ai_seat_profiles.clone(),
+                stacked_shoe_file: settings.stacked_shoe_file.clone(),
+            },
+            counters: CountersReport {
+                hands_played: stats.hands_played_session,
+                wins: stats.total_wins,
+                losses: stats.total_losses,
+                pushes: stats.total_pushes,
+                blackjacks: stats.blackjacks_dealt_player,
+                times_split: stats.times_split_chosen,
+                hands_involved_in_split: stats.hands_involved_in_split,
+                hands_after_splits: stats.total_hands_after_splits,
+                split_hand_parts_resolved: stats.num_resolved_split_hands,
+                times_doubled: stats.times_doubled_chosen,
+                hands_involved_in_double: stats.hands_involved_in_double,
+                doubled_hands_resolved: stats.num_resolved_doubled_hands,
+                undos_used: stats.undos_used,
+                redos_used: stats.redos_used,
+            },
+            metrics: MetricsReport {
+                default_bet: stats.initial_default_bet.as_f64(),
+                initial_balance: stats.initial_balance.as_f64(),
+                final_balance: stats.final_balance.as_f64(),
+                highest_balance: stats.highest_balance_session.as_f64(),
+                lowest_balance: stats.lowest_balance_session.as_f64(),
+                net_profit_loss: stats.net_profit_loss.as_f64(),
+                avg_profit_loss_per_hand: stats.avg_earn_loss_per_main_hand,
+                ev_per_initial_bet_pct: pct(stats.avg_earn_loss_per_main_hand, stats.initial_default_bet.as_f64()),
+                win_rate_pct: pct(stats.total_wins as f64, resolved_hands),
+                split_net_profit_loss: stats.earnings_from_split_hands.as_f64(),
+                avg_profit_loss_per_split_part: stats.avg_earn_loss_per_split_hand_part,
+                doubled_net_profit_loss: stats.earnings_from_doubled_hands.as_f64(),
+                avg_profit_loss_per_doubled_hand: stats.avg_earn_loss_per_doubled_hand,
+                trainer_accuracy_pct: stats.trainer_stats.as_ref()
+                    .filter(|t| t.total_decisions() > 0)
+                    .map(|t| pct(t.total_correct() as f64, t.total_decisions() as f64)),
+            },
+            confidence: {
+                let results = &stats.round_results;
+                let bet = stats.initial_default_bet.as_f64();
+                let ci = stats.ev_per_initial_bet_pct_with_ci();
+                ConfidenceReport {
+                    rounds: results.rounds,
+                    variance_per_round_bets: results.variance().map(|v| v / (bet * bet)),
+                    std_dev_per_hand_bets: results.std_dev().map(|sd| sd / bet),
+                    std_error_bets: results.std_error().map(|se| se / bet),
+                    ev_ci95_low_pct: ci.map(|(_, low, _)| low),
+                    ev_ci95_high_pct: ci.map(|(_, _, high)| high),
+                    target_precision_pct: config::TARGET_EV_PRECISION_PCT,
+                    hands_for_target_precision: results.rounds_for_precision(bet * config::TARGET_EV_PRECISION_PCT / 100.0),
+                }
+            },
+            swings: {
+                let swings = &stats.swings;
+                SwingsReport {
+                    longest_win_streak: swings.longest_win_streak,
+                    longest_loss_streak: swings.longest_loss_streak,
+                    largest_round_win: swings.largest_win.map(|(_, net)| net.as_f64()),
+                    largest_round_win_round: swings.largest_win.map(|(round, _)| round),
+                    largest_round_loss: swings.largest_loss.map(|(_, net)| net.as_f64()),
+                    largest_round_loss_round: swings.largest_loss.map(|(round, _)| round),
+                    shoes: swings.shoes.iter().filter(|s| s.rounds > 0).count(),
+                    winning_shoes: swings.winning_shoes(),
+                    best_shoe: swings.best_shoe().map(|m| m.as_f64()),
+                    worst_shoe: swings.worst_shoe().map(|m| m.as_f64()),
+                    stop_session_hands: swings.session_hands,
+                    stop_win_bets: config::STOP_WIN_BETS,
+                    stop_loss_bets: config::STOP_LOSS_BETS,
+                    stop_sessions: swings.stop_sessions,
+                    stop_win_first: swings.stop_win_first,
+                    stop_loss_first: swings.stop_loss_first,
+                }
+            },
+            timing: TimingReport {
+                started_at: chrono::DateTime::from_timestamp(stats.run_id as i64, 0)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+                runtime_seconds: stats.total_script_runtime_seconds,
+                avg_time_per_hand_seconds: stats.avg_time_per_hand_seconds,
+                hands_per_second: if stats.total_script_runtime_seconds > 0.0 { hands / stats.total_script_runtime_seconds } else { 0.0 },
+            },
+        }
+    }
+
+    // Column names and values for the results table; nested sections become "section.field"
+    fn csv_columns(&self) -> Result<Vec<(String, String)>, serde_json::Error> {
+        let mut columns = Vec::new();
+        flatten_into(&mut columns, "", &serde_json::to_value(self)?);
+        Ok(columns)
+    }
+}
+
+fn flatten_into(columns: &mut Vec<(String, String)>, prefix: &str, value: &Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_into(columns, &name, field);
+            }
+        }
+        Value::Null => columns.push((prefix.to_string(), String::new())),
+        Value::String(s) => columns.push((prefix.to_string(), s.clone())),
+        other => columns.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn report_path(run_id: u64) -> PathBuf {
+    PathBuf::from(&config::settings().output_dir).join(format!("{}{}", run_id, config::REPORT_SUFFIX))
+}
+
+// Writes <RUN_ID>_report.json and appends a row to the results table; returns both paths
+pub fn write_session_report(report: &SessionReport) -> Result<(PathBuf, PathBuf), Box<dyn std::error::Error>> {
+    let json_path = report_path(report.run_id);
+    fs::create_dir_all(&config::settings().output_dir)?;
+    fs::write(&json_path, serde_json::to_string_pretty(report)?)?;
+
+    let csv_path = config::settings().results_table_path();
+    append_csv_row(&csv_path, &report.csv_columns()?)?;
+    Ok((json_path, csv_path))
+}
+
+// Writes the header for a new table. Refuses to append to a table with other columns,
+// e.g. one written by an older version, rather than misaligning the rows.
+fn append_csv_row(path: &Path, columns: &[(String, String)]) -> Result<(), Box<dyn std::error::Error>> {
+    let header = columns.iter().map(|(name, _)| csv_field(name)).collect::<Vec<_>>().join(",");
+    let row = columns.iter().map(|(_, value)| csv_field(value)).collect::<Vec<_>>().join(",");
+    let existing_header = fs::read_to_string(path).ok().and_then(|contents| contents.lines().next().map(str::to_string));
+    let mut text = String::new();
+    match existing_header {
+        Some(existing) if existing != header => {
+            return Err(format!("{} has different columns; move it aside to start a new results table.", path.display()).into());
+        }
+        Some(_) => {}
+        None => text.push_str(&format!("{}\n", header)),
+    }
+    text.push_str(&format!("{}\n", row));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // One write per row, so parallel runs appending to a shared table don't interleave lines
+    OpenOptions::new().create(true).append(true).open(path)?.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+pub fn html_report_path(run_id: u64) -> PathBuf {
+    PathBuf::from(&config::settings().output_dir).join(format!("{}{}", run_id, config::HTML_REPORT_SUFFIX))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Two-column table of report fields, names without their section prefix
+fn html_field_table(columns: &[&(String, String)], prefix: &str) -> String {
+    let mut html = String::from("<table class=\"fields\">\n");
+    for (name, value) in columns {
+        let short_name = name.strip_prefix(prefix).unwrap_or(name);
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", html_escape(short_name), html_escape(value)));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+// The EV matrix in the strategy chart layout, colored like the EV heatmap
+fn html_ev_matrix(matrix: &EvMatrix) -> String {
+    let mut html = String::from("<table class=\"matrix\">\n<tr><th></th>");
+    for upcard in 2..=11u8 {
+        html.push_str(&format!("<th>{}</th>", upcard_label(upcard)));
+    }
+    html.push_str("</tr>\n");
+    for (kind, total) in matrix.rows() {
+        html.push_str(&format!("<tr><th>{}</th>", html_escape(&row_label(kind, total))));
+        for upcard in 2..=11u8 {
+            match matrix.cells.get(&ChartCell { kind, total, upcard }) {
+                Some(stats) => {
+                    let color = ev_color(stats.ev_per_hand());
+                    html.push_str(&format!(
+                        "<td style=\"background:rgb({},{},{})\" title=\"{} rounds, W/L/P {}/{}/{}, {:+.1} bets\">{:+.0} {}</td>",
+                        color.0, color.1, color.2, stats.rounds, stats.wins, stats.losses, stats.pushes, stats.net_units,
+                        stats.ev_per_hand() * 100.0, stats.actions.dominant_letter()));
+                }
+                None => html.push_str("<td class=\"empty\"></td>"),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+// SVG graphs are inlined as markup, bitmaps as base64 data URIs, so the page needs no other files
+fn html_graph(title: &str, path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let is_svg = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    let image = if is_svg {
+        let svg = fs::read_to_string(path)?;
+        let start = svg.find("<svg").unwrap_or(0); // Drop any XML declaration
+        svg[start..].to_string()
+    } else {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(fs::read(path)?);
+        format!("<img alt=\"{}\" src=\"data:image/png;base64,{}\">", html_escape(title), encoded)
+    };
+    Ok(format!("<figure>\n{}\n<figcaption>{}</figcaption>\n</figure>\n", image, html_escape(title)))
+}
+
+// Writes <RUN_ID>_report.html: the rules, the session stats, the EV matrix and the given graphs
+// (title, image file) in one self-contained page. Graphs that can't be read are left out.
+pub fn write_html_report(report: &SessionReport, matrix: &EvMatrix, graphs: &[(String, PathBuf)]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let columns = report.csv_columns()?;
+    let in_section = |section: &str| -> Vec<&(String, String)> {
+        columns.iter().filter(|(name, _)| name.starts_with(&format!("{}.", section))).collect()
+    };
+    let run_fields: Vec<&(String, String)> = columns.iter().filter(|(name, _)| !name.contains('.')).collect();
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Blackjack Session {}</title>\n<style>\n{}</style>\n</head>\n<body>\n",
+        report.run_id, HTML_REPORT_STYLE);
+    html.push_str(&format!("<h1>Blackjack Session {} ({})</h1>\n<p>{}</p>\n",
+        report.run_id, html_escape(&report.mode), html_escape(&report.rules.summary)));
+    html.push_str("<h2>Run</h2>\n");
+    html.push_str(&html_field_table(&run_fields, ""));
+    html.push_str("<h2>Rules</h2>\n");
+    html.push_str(&html_field_table(&in_section("rules"), "rules."));
+    for (section, heading) in [("counters", "Counters"), ("metrics", "Results"), ("confidence", "Confidence"), ("swings", "Streaks and Swings"), ("timing", "Timing")] {
+        html.push_str(&format!("<h2>{}</h2>\n", heading));
+        html.push_str(&html_field_table(&in_section(section), &format!("{}.", section)));
+    }
+
+    html.push_str(&format!("<h2>EV by Starting Hand vs Dealer Upcard</h2>\n<p>{} rounds; EV per hand in % of the initial bet and the most common first action. Hover a cell for its counts.</p>\n",
+        matrix.total_rounds()));
+    html.push_str(&html_ev_matrix(matrix));
+
+    if !graphs.is_empty() {
+        html.push_str("<h2>Graphs</h2>\n");
+    }
+    for (title, path) in graphs {
+        match html_graph(title, path) {
+            Ok(figure) => html.push_str(&figure),
+            Err(e) => log::warn!("Leaving graph {} out of the HTML report: {}", path.display(), e),
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+
+    let path = html_report_path(report.run_id);
+    fs::create_dir_all(&config::settings().output_dir)?;
+    fs::write(&path, html)?;
+    Ok(path)
+}
+
+const HTML_REPORT_STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 3px 8px; }
+table.fields th { text-align: left; font-weight: normal; color: #555; }
+table.matrix td { text-align: center; min-width: 3.5em; }
+table.matrix td.empty { background: #e6e6e6; }
+figure { margin: 1em 0; }
+figure svg, figure img { max-width: 100%; height: auto; }
+figcaption { color: #555; }
+";
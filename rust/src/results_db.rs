@@ -0,0 +1,290 @@
+// src/results_db.rs
+use crate::config;
+use crate::hand::HandEvent;
+use crate::history::RoundRecord;
+use crate::report::SessionReport;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs;
+use std::path::PathBuf;
+
+// Finished sessions in a local SQLite file, to find and compare runs without grepping the logs.
+// `runs` holds the key fields as columns (plus the whole report as JSON); `rounds` one summary
+// row per round of "Your" play. Money amounts are in dollars, as in the session report.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id INTEGER PRIMARY KEY,
+    started_at TEXT NOT NULL,
+    mode TEXT NOT NULL,
+    practice INTEGER NOT NULL,
+    seed INTEGER,
+    rules TEXT NOT NULL,
+    num_decks INTEGER NOT NULL,
+    blackjack_payout TEXT NOT NULL,
+    num_players INTEGER NOT NULL,
+    your_seat INTEGER NOT NULL,
+    ai_seat_profiles TEXT NOT NULL,
+    default_bet REAL NOT NULL,
+    hands_played INTEGER NOT NULL,
+    initial_balance REAL NOT NULL,
+    final_balance REAL NOT NULL,
+    net_profit_loss REAL NOT NULL,
+    ev_per_initial_bet_pct REAL NOT NULL,
+    ev_ci95_low_pct REAL,
+    ev_ci95_high_pct REAL,
+    std_dev_per_hand_bets REAL,
+    win_rate_pct REAL NOT NULL,
+    blackjacks INTEGER NOT NULL,
+    runtime_seconds REAL NOT NULL,
+    report_json TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS rounds (
+    run_id INTEGER NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    round INTEGER NOT NULL,
+    shoe_cards_remaining INTEGER NOT NULL,
+    your_hands INTEGER NOT NULL,
+    your_bet REAL NOT NULL,
+    net_result REAL NOT NULL,
+    balance_after REAL NOT NULL,
+    dealer_upcard TEXT,
+    dealer_value INTEGER NOT NULL,
+    flags TEXT NOT NULL, -- e.g. \"blackjack\", \"split,double\"
+    PRIMARY KEY (run_id, round)
+);
+CREATE INDEX IF NOT EXISTS runs_by_rules ON runs(rules);
+";
+
+pub fn open_results_db() -> Result<Connection, Box<dyn std::error::Error>> {
+    let path = config::settings().results_db_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let connection = Connection::open(&path)?;
+    connection.execute_batch(&format!("PRAGMA foreign_keys = ON;{}", SCHEMA))?;
+    Ok(connection)
+}
+
+// Notable events of "Your" hands in a round, comma separated
+fn round_flags(record: &RoundRecord) -> String {
+    let Some(you) = record.seats.iter().find(|s| s.is_user) else { return String::new(); };
+    let mut flags = Vec::new();
+    if you.hands.len() == 1 && you.hands[0].to_hand().is_natural_blackjack() {
+        flags.push("blackjack");
+    }
+    let any_event = |wanted: fn(&HandEvent) -> bool| you.hands.iter().any(|h| h.events.iter().any(wanted));
+    if any_event(|e| matches!(e, HandEvent::Split(_))) {
+        flags.push("split");
+    }
+    if any_event(|e| matches!(e, HandEvent::Double(_))) {
+        flags.push("double");
+    }
+    flags.join(",")
+}
+
+// Stores (or replaces) a finished run and its rounds; returns the database path
+pub fn store_run(report: &SessionReport, rounds: &[RoundRecord]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut connection = open_results_db()?;
+    let transaction = connection.transaction()?;
+    // Replacing cascades to the run's old rounds
+    transaction.execute("DELETE FROM runs WHERE run_id = ?1", params![report.run_id as i64])?;
+    transaction.execute(
+        "INSERT INTO runs (run_id, started_at, mode, practice, seed, rules, num_decks, blackjack_payout, num_players,
+            your_seat, ai_seat_profiles, default_bet, hands_played, initial_balance, final_balance, net_profit_loss,
+            ev_per_initial_bet_pct, ev_ci95_low_pct, ev_ci95_high_pct, std_dev_per_hand_bets, win_rate_pct, blackjacks,
+            runtime_seconds, report_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+        params![
+            report.run_id as i64, report.timing.started_at, report.mode, report.practice, report.seed.map(|s| s as i64),
+            report.rules.summary, report.rules.num_decks as i64, report.rules.blackjack_payout, report.rules.num_players as i64,
+            report.rules.your_seat as i64, report.rules.ai_seat_profiles, report.metrics.default_bet,
+            report.counters.hands_played, report.metrics.initial_balance, report.metrics.final_balance,
+            report.metrics.net_profit_loss, report.metrics.ev_per_initial_bet_pct, report.confidence.ev_ci95_low_pct,
+            report.confidence.ev_ci95_high_pct, report.confidence.std_dev_per_hand_bets, report.metrics.win_rate_pct,
+            report.counters.blackjacks, report.timing.runtime_seconds, serde_json::to_string(report)?,
+        ],
+    )?;
+    {
+        let mut insert_round = transaction.prepare(
+            "INSERT OR REPLACE INTO rounds (run_id, round, shoe_cards_remaining, your_hands, your_bet, net_result, balance_after,
+                dealer_upcard, dealer_value, flags)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+        for record in rounds.iter().filter(|r| r.run_id == report.run_id) {
+            let you = record.seats.iter().find(|s| s.is_user);
+            let your_bet = you.map(|s| s.hands.iter().map(|h| h.bet.as_f64()).sum::<f64>()).unwrap_or(0.0);
+            insert_round.execute(params![
+                report.run_id as i64, record.round, record.shoe.cards_remaining as i64,
+                you.map(|s| s.hands.len() as i64).unwrap_or(0), your_bet,
+                (record.balance_after - record.balance_before).as_f64(), record.balance_after.as_f64(),
+                record.dealer_cards.first().map(|c| c.to_string()), record.dealer_value, round_flags(record),
+            ])?;
+        }
+    }
+    transaction.commit()?;
+    Ok(config::settings().results_db_path())
+}
+
+// Key fields of a stored run, as listed and compared
+struct RunRow {
+    run_id: i64,
+    started_at: String,
+    mode: String,
+    practice: bool,
+    seed: Option<i64>,
+    rules: String,
+    ai_seat_profiles: String,
+    default_bet: f64,
+    hands_played: i64,
+    final_balance: f64,
+    net_profit_loss: f64,
+    ev_per_initial_bet_pct: f64,
+    ev_ci95: Option<(f64, f64)>,
+    std_dev_per_hand_bets: Option<f64>,
+    win_rate_pct: f64,
+    blackjacks: i64,
+    runtime_seconds: f64,
+}
+
+const RUN_ROW_COLUMNS: &str = "run_id, started_at, mode, practice, seed, rules, ai_seat_profiles, default_bet, hands_played,
+    final_balance, net_profit_loss, ev_per_initial_bet_pct, ev_ci95_low_pct, ev_ci95_high_pct, std_dev_per_hand_bets,
+    win_rate_pct, blackjacks, runtime_seconds";
+
+impl RunRow {
+    fn from_sql(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let ci_low: Option<f64> = row.get(12)?;
+        let ci_high: Option<f64> = row.get(13)?;
+        Ok(RunRow {
+            run_id: row.get(0)?,
+            started_at: row.get(1)?,
+            mode: row.get(2)?,
+            practice: row.get(3)?,
+            seed: row.get(4)?,
+            rules: row.get(5)?,
+            ai_seat_profiles: row.get(6)?,
+            default_bet: row.get(7)?,
+            hands_played: row.get(8)?,
+            final_balance: row.get(9)?,
+            net_profit_loss: row.get(10)?,
+            ev_per_initial_bet_pct: row.get(11)?,
+            ev_ci95: ci_low.zip(ci_high),
+            std_dev_per_hand_bets: row.get(14)?,
+            win_rate_pct: row.get(15)?,
+            blackjacks: row.get(16)?,
+            runtime_seconds: row.get(17)?,
+        })
+    }
+}
+
+// Filters for listing runs; text filters match anywhere in the field, case-insensitively
+#[derive(Debug, Default)]
+pub struct RunFilter {
+    pub rules: Option<String>,
+    pub mode: Option<String>,
+    pub since: Option<String>, // Date or RFC 3339 time; runs started on or after it
+    pub limit: usize,
+}
+
+pub fn list_runs(filter: &RunFilter) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = config::settings().results_db_path();
+    if !db_path.exists() {
+        println!("No results database at {} yet; it is created when a session finishes.", db_path.display());
+        return Ok(());
+    }
+    let connection = open_results_db()?;
+    let mut statement = connection.prepare(&format!(
+        "SELECT {} FROM runs
+         WHERE (?1 IS NULL OR rules LIKE '%' || ?1 || '%')
+           AND (?2 IS NULL OR mode LIKE '%' || ?2 || '%')
+           AND (?3 IS NULL OR started_at >= ?3)
+         ORDER BY run_id DESC LIMIT ?4",
+        RUN_ROW_COLUMNS,
+    ))?;
+    let runs = statement
+        .query_map(params![filter.rules, filter.mode, filter.since, filter.limit as i64], RunRow::from_sql)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    println!("{} run(s) in {}", runs.len(), db_path.display());
+    println!("{:<12} {:<20} {:<40} {:>7} {:>11} {:>8}  Mode", "RUN_ID", "Started", "Rules", "Hands", "Net", "EV %");
+    for run in &runs {
+        println!("{:<12} {:<20} {:<40} {:>7} {:>11} {:>+8.2}  {}{}",
+            run.run_id, run.started_at.get(..19).unwrap_or(&run.started_at).replace('T', " "), run.rules, run.hands_played,
+            format!("${:+.2}", run.net_profit_loss), run.ev_per_initial_bet_pct, run.mode, if run.practice { " [practice]" } else { "" });
+    }
+    Ok(())
+}
+
+fn load_run(connection: &Connection, run_id: u64) -> Result<RunRow, Box<dyn std::error::Error>> {
+    connection
+        .query_row(&format!("SELECT {} FROM runs WHERE run_id = ?1", RUN_ROW_COLUMNS), params![run_id as i64], RunRow::from_sql)
+        .optional()?
+        .ok_or_else(|| format!("Run {} is not in the results database.", run_id).into())
+}
+
+// Largest drop from a running peak of the balance, over the run's stored rounds
+fn max_drawdown(connection: &Connection, run: &RunRow) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+    let mut statement = connection.prepare("SELECT net_result, balance_after FROM rounds WHERE run_id = ?1 ORDER BY round")?;
+    let rounds = statement
+        .query_map(params![run.run_id], |row| Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(&(first_net, first_after)) = rounds.first() else { return Ok(None); };
+    let mut peak = first_after - first_net; // Balance before the first stored round
+    let mut drawdown: f64 = 0.0;
+    for &(_, balance) in &rounds {
+        peak = peak.max(balance);
+        drawdown = drawdown.max(peak - balance);
+    }
+    Ok(Some(drawdown))
+}
+
+// Key metrics of two runs side by side, with the difference (B - A) where it is a number
+pub fn diff_runs(run_a: u64, run_b: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let connection = open_results_db()?;
+    let a = load_run(&connection, run_a)?;
+    let b = load_run(&connection, run_b)?;
+    let (drawdown_a, drawdown_b) = (max_drawdown(&connection, &a)?, max_drawdown(&connection, &b)?);
+
+    // Text fields don't fit the columns: one line when both runs agree, else one line per run
+    let text = |name: &str, va: String, vb: String| -> Vec<String> {
+        if va == vb {
+            vec![format!("{:<22} {}", name, va)]
+        } else {
+            vec![format!("{:<22} A: {}", name, va), format!("{:<22} B: {}", "", vb)]
+        }
+    };
+    let number = |name: &str, va: Option<f64>, vb: Option<f64>, decimals: usize| {
+        let show = |v: Option<f64>| v.map(|v| format!("{:.*}", decimals, v)).unwrap_or_else(|| "-".to_string());
+        let delta = match (va, vb) {
+            (Some(va), Some(vb)) => format!("{:+.*}", decimals, vb - va),
+            _ => String::new(),
+        };
+        vec![format!("{:<22} {:>24} {:>24} {:>12}", name, show(va), show(vb), delta)]
+    };
+    let ci = |run: &RunRow| run.ev_ci95.map(|(low, high)| format!("{:+.2} to {:+.2}", low, high)).unwrap_or_else(|| "-".to_string());
+
+    let seed = |run: &RunRow| run.seed.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string());
+
+    let sections = vec![
+        vec![format!("{:<22} {:>24} {:>24} {:>12}", "", format!("Run A ({})", a.run_id), format!("Run B ({})", b.run_id), "B - A")],
+        text("Started", a.started_at.clone(), b.started_at.clone()),
+        text("Mode", a.mode.clone(), b.mode.clone()),
+        text("Rules", a.rules.clone(), b.rules.clone()),
+        text("AI Seats", a.ai_seat_profiles.clone(), b.ai_seat_profiles.clone()),
+        text("Seed", seed(&a), seed(&b)),
+        text("Practice", a.practice.to_string(), b.practice.to_string()),
+        number("Default Bet ($)", Some(a.default_bet), Some(b.default_bet), 2),
+        number("Hands Played", Some(a.hands_played as f64), Some(b.hands_played as f64), 0),
+        number("Final Balance ($)", Some(a.final_balance), Some(b.final_balance), 2),
+        number("Net P/L ($)", Some(a.net_profit_loss), Some(b.net_profit_loss), 2),
+        number("EV per Bet (%)", Some(a.ev_per_initial_bet_pct), Some(b.ev_per_initial_bet_pct), 2),
+        vec![format!("{:<22} {:>24} {:>24}", "EV 95% CI (%)", ci(&a), ci(&b))],
+        number("Std. Dev. (bets)", a.std_dev_per_hand_bets, b.std_dev_per_hand_bets, 3),
+        number("Win Rate (%)", Some(a.win_rate_pct), Some(b.win_rate_pct), 2),
+        number("Blackjacks", Some(a.blackjacks as f64), Some(b.blackjacks as f64), 0),
+        number("Max Drawdown ($)", drawdown_a, drawdown_b, 2),
+        number("Runtime (s)", Some(a.runtime_seconds), Some(b.runtime_seconds), 1),
+    ];
+    for line in sections.into_iter().flatten() {
+        println!("{}", line);
+        log::info!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+// src/session.rs
+use crate::card_deck::Deck;
+use crate::config;
+use crate::graph::BalanceEvent;
+use crate::money::Money;
+use crate::player::TableLayout;
+use crate::stats::SessionStats;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Everything needed to pick an interactive session up between hands, mid-shoe included.
+// The deck carries its remaining order, discards and RNG state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub rules: String, // config::rules_summary() when saved
+    pub table: String, // TableLayout::describe() when saved
+    pub profile_name: Option<String>,
+    pub deck: Deck,
+    pub your_balance: Money,
+    pub balance_history: Vec<f64>,
+    #[serde(default)]
+    pub balance_events: Vec<(usize, BalanceEvent)>, // Markers for the balance graph, see graph::BalanceEvent
+    pub session_stats: SessionStats,
+}
+
+impl SavedSession {
+    // The shoe and stats only make sense under the rules and table they were played with
+    pub fn check_compatible(&self, table_layout: &TableLayout) -> Result<(), String> {
+        if self.rules != config::rules_summary() {
+            return Err(format!("Saved session was played with rules [{}], current rules are [{}].",
+                self.rules, config::rules_summary()));
+        }
+        if self.table != table_layout.describe() {
+            return Err(format!("Saved session was played at a different table ({}), current table: {}.",
+                self.table, table_layout.describe()));
+        }
+        Ok(())
+    }
+
+    pub fn summary_line(&self) -> String {
+        format!("Run {}: {}, Balance ${}, Hands {}, {} cards left in shoe{}",
+            self.session_stats.run_id, self.session_stats.mode, self.your_balance,
+            self.session_stats.hands_played_session, self.deck.len(),
+            match &self.profile_name { Some(name) => format!(", Profile {}", name), None => String::new() })
+    }
+}
+
+fn saves_dir() -> PathBuf {
+    PathBuf::from(config::SAVES_DIR_NAME)
+}
+
+pub fn save_session(saved: &SavedSession) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    fs::create_dir_all(saves_dir())?;
+    let path = saves_dir().join(format!("session_{}.json", saved.session_stats.run_id));
+    fs::write(&path, serde_json::to_string_pretty(saved)?)?;
+    Ok(path)
+}
+
+fn load_session(path: &Path) -> Result<SavedSession, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn list_saved_sessions() -> Result<Vec<(PathBuf, SavedSession)>, Box<dyn std::error::Error>> {
+    let dir = saves_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        match load_session(&path) {
+            Ok(saved) => sessions.push((path, saved)),
+            Err(e) => log::warn!("Skipping unreadable saved session {:?}: {}", path, e),
+        }
+    }
+    sessions.sort_by_key(|(_, saved)| saved.session_stats.run_id);
+    Ok(sessions)
+}
+
+// The saved session waiting to be resumed for this profile, if any
+pub fn saved_session_for_profile(profile_name: &str) -> Result<Option<SavedSession>, Box<dyn std::error::Error>> {
+    Ok(list_saved_sessions()?.into_iter()
+        .map(|(_, saved)| saved)
+        .find(|saved| saved.profile_name.as_deref() == Some(profile_name)))
+}
+
+// Lists saved sessions and loads the chosen one, returning it with its save file. The file is
+// kept until the resumed session is saved again (same file) or finishes (see remove_saved_session),
+// so a crash mid-session does not lose it.
+pub fn prompt_for_saved_session(table_layout: &TableLayout) -> Result<Option<(PathBuf, SavedSession)>, Box<dyn std::error::Error>> {
+    let mut sessions = list_saved_sessions()?;
+    if sessions.is_empty() {
+        println!("No saved sessions in '{}/'.", config::SAVES_DIR_NAME);
+        return Ok(None);
+    }
+    for (i, (_, saved)) in sessions.iter().enumerate() {
+        println!("{}) {}", i + 1, saved.summary_line());
+    }
+    let choice = utils::get_user_input("Resume which session? (number, Enter to cancel): ");
+    let index = match choice.parse::<usize>() {
+        Ok(n) if (1..=sessions.len()).contains(&n) => n - 1,
+        _ => return Ok(None),
+    };
+    let (path, saved) = sessions.swap_remove(index);
+    saved.check_compatible(table_layout)?;
+    Ok(Some((path, saved)))
+}
+
+// Called once a resumed session has finished, so the same shoe cannot be replayed from the save
+pub fn remove_saved_session(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
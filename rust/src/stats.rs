@@ -1,13 +1,290 @@
 // src/stats.rs
 use crate::config; // For DEFAULT_BET
-use crate::config::{LOGS_DIR_NAME, TEXT_LOG_FILENAME};
+use crate::config::LogFormat;
+use crate::ev_matrix::EvMatrix;
+use crate::money::Money;
+use crate::trainer::TrainerStats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
 
-#[derive(Debug, Clone)]
+// Running mean and variance of "Your" net result per round (Welford's method), in dollars
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoundResultStats {
+    pub rounds: u32,
+    pub mean: f64,
+    pub m2: f64, // Sum of squared deviations from the mean
+}
+
+impl RoundResultStats {
+    pub fn add(&mut self, net_result: Money) {
+        let x = net_result.as_f64();
+        self.rounds += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.rounds as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    // Sample variance per round; needs at least two rounds
+    pub fn variance(&self) -> Option<f64> {
+        if self.rounds < 2 { None } else { Some(self.m2 / (self.rounds - 1) as f64) }
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    // Standard error of the mean result per round
+    pub fn std_error(&self) -> Option<f64> {
+        self.std_dev().map(|sd| sd / (self.rounds as f64).sqrt())
+    }
+
+    // 95% confidence interval on the mean result per round
+    pub fn confidence_interval_95(&self) -> Option<(f64, f64)> {
+        self.std_error().map(|se| (self.mean - Z_95 * se, self.mean + Z_95 * se))
+    }
+
+    // Rounds needed for the 95% interval to be +/- `precision` (same unit as the results)
+    pub fn rounds_for_precision(&self, precision: f64) -> Option<u64> {
+        if precision <= 0.0 {
+            return None;
+        }
+        self.std_dev().map(|sd| (Z_95 * sd / precision).powi(2).ceil() as u64)
+    }
+}
+
+const Z_95: f64 = 1.96;
+
+// Streak lengths from this one up are counted together in the streak tables
+const STREAK_TABLE_MAX: u32 = 6;
+
+// Net result of one shoe, from deal to reshuffle
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShoeResult {
+    pub rounds: u32,
+    pub net_result: Money,
+}
+
+// Streaks, swings and stop-win/stop-loss hits of "Your" rounds, for the "hot/cold table" questions.
+// A round is won or lost by its net result over all of "Your" hands; pushes neither extend nor break a streak.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwingStats {
+    pub current_streak: i32, // > 0: wins in a row, < 0: losses in a row
+    pub longest_win_streak: u32,
+    pub longest_loss_streak: u32,
+    pub win_streaks: BTreeMap<u32, u32>, // Finished streaks: length -> count
+    pub loss_streaks: BTreeMap<u32, u32>,
+    pub decided_rounds: u32, // Rounds won or lost
+    pub rounds_won: u32,
+    pub largest_win: Option<(u32, Money)>, // (round, net result)
+    pub largest_loss: Option<(u32, Money)>,
+    pub shoes: Vec<ShoeResult>, // The last one is the shoe in play
+
+    // Stop-win/stop-loss: the session is cut into sessions of `session_hands` rounds and each
+    // is checked for which threshold its running net result reached first
+    pub session_hands: u32,
+    pub stop_sessions: u32, // Full sessions checked
+    pub stop_win_first: u32,
+    pub stop_loss_first: u32,
+    current_session_rounds: u32,
+    current_session_net: Money,
+    current_session_stop: Option<bool>, // Some(true) once stop-win was reached first
+}
+
+impl Default for SwingStats {
+    fn default() -> Self {
+        SwingStats {
+            current_streak: 0,
+            longest_win_streak: 0,
+            longest_loss_streak: 0,
+            win_streaks: BTreeMap::new(),
+            loss_streaks: BTreeMap::new(),
+            decided_rounds: 0,
+            rounds_won: 0,
+            largest_win: None,
+            largest_loss: None,
+            shoes: Vec::new(),
+            session_hands: config::SESSION_LENGTH_HANDS,
+            stop_sessions: 0,
+            stop_win_first: 0,
+            stop_loss_first: 0,
+            current_session_rounds: 0,
+            current_session_net: Money::ZERO,
+            current_session_stop: None,
+        }
+    }
+}
+
+impl SwingStats {
+    pub fn add_round(&mut self, round: u32, net_result: Money, default_bet: Money) {
+        if net_result != Money::ZERO {
+            let won = net_result > Money::ZERO;
+            self.decided_rounds += 1;
+            if won {
+                self.rounds_won += 1;
+            }
+            if (self.current_streak > 0) != won && self.current_streak != 0 {
+                self.finish_streak();
+            }
+            self.current_streak += if won { 1 } else { -1 };
+            let length = self.current_streak.unsigned_abs();
+            if won {
+                self.longest_win_streak = self.longest_win_streak.max(length);
+            } else {
+                self.longest_loss_streak = self.longest_loss_streak.max(length);
+            }
+        }
+        if net_result > Money::ZERO && self.largest_win.is_none_or(|(_, best)| net_result > best) {
+            self.largest_win = Some((round, net_result));
+        }
+        if net_result < Money::ZERO && self.largest_loss.is_none_or(|(_, worst)| net_result < worst) {
+            self.largest_loss = Some((round, net_result));
+        }
+
+        if self.shoes.is_empty() {
+            self.shoes.push(ShoeResult::default());
+        }
+        if let Some(shoe) = self.shoes.last_mut() {
+            shoe.rounds += 1;
+            shoe.net_result += net_result;
+        }
+
+        self.current_session_rounds += 1;
+        self.current_session_net += net_result;
+        if self.current_session_stop.is_none() {
+            if self.current_session_net >= default_bet * config::STOP_WIN_BETS {
+                self.current_session_stop = Some(true);
+            } else if self.current_session_net <= -(default_bet * config::STOP_LOSS_BETS) {
+                self.current_session_stop = Some(false);
+            }
+        }
+        if self.current_session_rounds >= self.session_hands.max(1) {
+            self.stop_sessions += 1;
+            match self.current_session_stop {
+                Some(true) => self.stop_win_first += 1,
+                Some(false) => self.stop_loss_first += 1,
+                None => {}
+            }
+            self.current_session_rounds = 0;
+            self.current_session_net = Money::ZERO;
+            self.current_session_stop = None;
+        }
+    }
+
+    fn finish_streak(&mut self) {
+        let length = self.current_streak.unsigned_abs();
+        let streaks = if self.current_streak > 0 { &mut self.win_streaks } else { &mut self.loss_streaks };
+        *streaks.entry(length).or_insert(0) += 1;
+        self.current_streak = 0;
+    }
+
+    // Called when the shoe is reshuffled; the rounds after it count towards a new shoe
+    pub fn start_new_shoe(&mut self) {
+        if let Some(shoe) = self.shoes.last().filter(|s| s.rounds > 0) {
+            log::info!("Shoe #{} finished (You): ${:+} over {} rounds", self.shoes.len(), shoe.net_result, shoe.rounds);
+            self.shoes.push(ShoeResult::default());
+        }
+    }
+
+    // Finished streaks plus the one still running: (win streaks, loss streaks), length -> count
+    fn streak_counts(&self) -> (BTreeMap<u32, u32>, BTreeMap<u32, u32>) {
+        let mut finished = self.clone();
+        if finished.current_streak != 0 {
+            finished.finish_streak();
+        }
+        (finished.win_streaks, finished.loss_streaks)
+    }
+
+    // Expected number of streaks of exactly `length` successes in `trials` independent trials
+    // with success probability `p`: a run inside the sequence needs a failure on both sides,
+    // one at either end of the sequence needs it on one side only.
+    fn expected_streaks(trials: u32, p: f64, length: u32) -> f64 {
+        let (n, k) = (trials as f64, length as f64);
+        if length > trials {
+            0.0
+        } else if length == trials {
+            p.powf(k)
+        } else {
+            p.powf(k) * (1.0 - p) * (2.0 + (n - k - 1.0) * (1.0 - p))
+        }
+    }
+
+    // Observed vs expected-if-independent streak counts by length; the last bucket is STREAK_TABLE_MAX and up
+    fn streak_table(&self, observed: &BTreeMap<u32, u32>, p: f64) -> String {
+        (1..=STREAK_TABLE_MAX).map(|length| {
+            let (count, expected) = if length < STREAK_TABLE_MAX {
+                (observed.get(&length).copied().unwrap_or(0), Self::expected_streaks(self.decided_rounds, p, length))
+            } else {
+                (observed.range(length..).map(|(_, c)| c).sum(),
+                 (length..=self.decided_rounds).map(|l| Self::expected_streaks(self.decided_rounds, p, l)).sum())
+            };
+            let label = if length < STREAK_TABLE_MAX { length.to_string() } else { format!("{}+", length) };
+            format!("{}: {}/{:.1}", label, count, expected)
+        }).collect::<Vec<_>>().join(", ")
+    }
+
+    pub fn winning_shoes(&self) -> usize {
+        self.shoes.iter().filter(|s| s.net_result > Money::ZERO).count()
+    }
+
+    pub fn best_shoe(&self) -> Option<Money> {
+        self.shoes.iter().filter(|s| s.rounds > 0).map(|s| s.net_result).max()
+    }
+
+    pub fn worst_shoe(&self) -> Option<Money> {
+        self.shoes.iter().filter(|s| s.rounds > 0).map(|s| s.net_result).min()
+    }
+
+    pub fn to_log_lines(&self, default_bet: Money) -> Vec<String> {
+        let mut lines = vec![
+            format!("Longest Win Streak (You): {}, Longest Loss Streak: {} (rounds; pushes don't break a streak)",
+                self.longest_win_streak, self.longest_loss_streak),
+        ];
+        let describe = |largest: Option<(u32, Money)>| match largest {
+            Some((round, net)) => format!("${:+} (round {})", net, round),
+            None => "-".to_string(),
+        };
+        lines.push(format!("Largest Single-Round Win (You): {}, Largest Loss: {}", describe(self.largest_win), describe(self.largest_loss)));
+        if self.decided_rounds > 0 {
+            let p = self.rounds_won as f64 / self.decided_rounds as f64;
+            let (win_streaks, loss_streaks) = self.streak_counts();
+            lines.push(format!("Win Streak Lengths (You; observed/expected if rounds were independent, p = {:.3}): {}",
+                p, self.streak_table(&win_streaks, p)));
+            lines.push(format!("Loss Streak Lengths (You; observed/expected if rounds were independent, p = {:.3}): {}",
+                1.0 - p, self.streak_table(&loss_streaks, 1.0 - p)));
+        }
+        let played_shoes: Vec<&ShoeResult> = self.shoes.iter().filter(|s| s.rounds > 0).collect();
+        if let (Some(best), Some(worst)) = (self.best_shoe(), self.worst_shoe()) {
+            let total: Money = played_shoes.iter().fold(Money::ZERO, |sum, s| sum + s.net_result);
+            lines.push(format!("Shoes (You): {} (last may be partial), Winning: {} ({:.1}%), Best: ${:+}, Worst: ${:+}, Avg: ${:+.2}",
+                played_shoes.len(), self.winning_shoes(), self.winning_shoes() as f64 / played_shoes.len() as f64 * 100.0,
+                best, worst, total.as_f64() / played_shoes.len() as f64));
+        }
+        if self.stop_sessions > 0 {
+            let share = |count: u32| count as f64 / self.stop_sessions as f64 * 100.0;
+            let neither = self.stop_sessions - self.stop_win_first - self.stop_loss_first;
+            lines.push(format!("Stop-Win ${:+} / Stop-Loss ${:+} in {}-Hand Sessions (You): {} sessions, stop-win first {} ({:.1}%), stop-loss first {} ({:.1}%), neither {} ({:.1}%)",
+                default_bet * config::STOP_WIN_BETS, -(default_bet * config::STOP_LOSS_BETS), self.session_hands, self.stop_sessions,
+                self.stop_win_first, share(self.stop_win_first), self.stop_loss_first, share(self.stop_loss_first), neither, share(neither)));
+        }
+        lines
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionStats {
     pub run_id: u64,
     pub mode: String,
+    pub ai_seat_profiles: String,       // e.g. "Seat 2: Book, Seat 3: Never Bust"
+    #[serde(default)]
+    pub ai_insurance_taken: u32,        // Insurance bets by AI seats (Tourists)
+    #[serde(default)]
+    pub ai_insurance_won: u32,          // Of those, dealer had Blackjack (pays 2:1)
+    pub practice_mode: bool,            // Undo was allowed; results must not count as real play
+    pub undos_used: u32,                // Undo steps taken in practice mode
+    #[serde(default)]
+    pub redos_used: u32,                // Redo steps, counted apart so they don't inflate the undos
+    pub trainer_stats: Option<TrainerStats>, // Some when trainer mode grades "Your" decisions
     pub target_iterations: Option<u32>, // Only for simulation
     pub hands_played_session: u32,      // "Your" main hands
     pub blackjacks_dealt_player: u32,   // "Your" blackjacks
@@ -23,20 +300,26 @@ pub struct SessionStats {
     pub total_losses: u32,              // "Your" losses
     pub total_pushes: u32,              // "Your" pushes
 
-    pub earnings_from_split_hands: f64, // "Your" P/L from hands that were part of a split
+    pub earnings_from_split_hands: Money, // "Your" P/L from hands that were part of a split
     pub num_resolved_split_hands: u32,  // "Your" count of individual hands resolved in a split
 
-    pub earnings_from_doubled_hands: f64,// "Your" P/L from hands that were doubled
+    pub earnings_from_doubled_hands: Money,// "Your" P/L from hands that were doubled
     pub num_resolved_doubled_hands: u32, // "Your" count of individual hands doubled & resolved
 
-    pub initial_default_bet: f64,
-    pub initial_balance: f64,
-    pub final_balance: f64,
-    pub highest_balance_session: f64,
-    pub lowest_balance_session: f64,
+    pub initial_default_bet: Money,
+    pub initial_balance: Money,
+    pub final_balance: Money,
+    pub highest_balance_session: Money,
+    pub lowest_balance_session: Money,
+    #[serde(default)] // Sessions saved before this was tracked
+    pub round_results: RoundResultStats,
+    #[serde(default)]
+    pub ev_matrix: EvMatrix, // "Your" results by starting hand and dealer upcard
+    #[serde(default)]
+    pub swings: SwingStats, // Streaks, largest swings, per-shoe results and stop-win/stop-loss hits
 
-    // Calculated at the end
-    pub net_profit_loss: f64,
+    // Calculated at the end (averages are in dollars and may have fractional cents)
+    pub net_profit_loss: Money,
     pub avg_earn_loss_per_main_hand: f64,
     pub avg_earn_loss_per_split_hand_part: f64,
     pub avg_earn_loss_per_doubled_hand: f64,
@@ -46,10 +329,17 @@ pub struct SessionStats {
 }
 
 impl SessionStats {
-    pub fn new(run_id: u64, mode_str: String, start_bal: f64) -> Self {
+    pub fn new(run_id: u64, mode_str: String, start_bal: Money) -> Self {
         SessionStats {
             run_id,
             mode: mode_str,
+            ai_seat_profiles: String::new(),
+            ai_insurance_taken: 0,
+            ai_insurance_won: 0,
+            practice_mode: false,
+            undos_used: 0,
+            redos_used: 0,
+            trainer_stats: None,
             target_iterations: None,
             hands_played_session: 0,
             blackjacks_dealt_player: 0,
@@ -61,16 +351,19 @@ impl SessionStats {
             total_wins: 0,
             total_losses: 0,
             total_pushes: 0,
-            earnings_from_split_hands: 0.0,
+            earnings_from_split_hands: Money::ZERO,
             num_resolved_split_hands: 0,
-            earnings_from_doubled_hands: 0.0,
+            earnings_from_doubled_hands: Money::ZERO,
             num_resolved_doubled_hands: 0,
-            initial_default_bet: config::DEFAULT_BET,
+            initial_default_bet: config::settings().default_bet,
             initial_balance: start_bal,
             final_balance: start_bal, // Will be updated
             highest_balance_session: start_bal,
             lowest_balance_session: start_bal,
-            net_profit_loss: 0.0,
+            round_results: RoundResultStats::default(),
+            ev_matrix: EvMatrix::default(),
+            swings: SwingStats::default(),
+            net_profit_loss: Money::ZERO,
             avg_earn_loss_per_main_hand: 0.0,
             avg_earn_loss_per_split_hand_part: 0.0,
             avg_earn_loss_per_doubled_hand: 0.0,
@@ -82,23 +375,30 @@ impl SessionStats {
     pub fn calculate_final_metrics(&mut self) {
         self.net_profit_loss = self.final_balance - self.initial_balance;
         if self.hands_played_session > 0 {
-            self.avg_earn_loss_per_main_hand = self.net_profit_loss / self.hands_played_session as f64;
+            self.avg_earn_loss_per_main_hand = self.net_profit_loss.as_f64() / self.hands_played_session as f64;
         } else {
             self.avg_earn_loss_per_main_hand = 0.0;
         }
         if self.num_resolved_split_hands > 0 {
-            self.avg_earn_loss_per_split_hand_part = self.earnings_from_split_hands / self.num_resolved_split_hands as f64;
+            self.avg_earn_loss_per_split_hand_part = self.earnings_from_split_hands.as_f64() / self.num_resolved_split_hands as f64;
         } else {
             self.avg_earn_loss_per_split_hand_part = 0.0;
         }
         if self.num_resolved_doubled_hands > 0 {
-            self.avg_earn_loss_per_doubled_hand = self.earnings_from_doubled_hands / self.num_resolved_doubled_hands as f64;
+            self.avg_earn_loss_per_doubled_hand = self.earnings_from_doubled_hands.as_f64() / self.num_resolved_doubled_hands as f64;
         } else {
             self.avg_earn_loss_per_doubled_hand = 0.0;
         }
     }
 
-    pub fn update_balance_extremes(&mut self, current_balance: f64) {
+    // EV per initial bet (mean result per round / default bet) with its 95% interval, as percentages
+    pub fn ev_per_initial_bet_pct_with_ci(&self) -> Option<(f64, f64, f64)> {
+        let bet = self.initial_default_bet.as_f64();
+        let (low, high) = self.round_results.confidence_interval_95()?;
+        Some((self.round_results.mean / bet * 100.0, low / bet * 100.0, high / bet * 100.0))
+    }
+
+    pub fn update_balance_extremes(&mut self, current_balance: Money) {
         if current_balance > self.highest_balance_session {
             self.highest_balance_session = current_balance;
         }
@@ -107,44 +407,82 @@ impl SessionStats {
         }
     }
 
-    pub fn to_log_lines(&self) -> Vec<String> {
+    // Short version of to_log_lines for the console at the end of an interactive session
+    pub fn to_summary_lines(&self) -> Vec<String> {
         let mut lines = vec![
             format!("Run ID: {}", self.run_id),
             format!("Mode: {}", self.mode),
         ];
+        if self.practice_mode {
+            lines.push(format!("Practice Session: Yes (undos: {}, redos: {}) - not real results", self.undos_used, self.redos_used));
+        }
+        lines.extend(vec![
+            format!("Hands Played by You (Main): {}", self.hands_played_session),
+            format!("Starting Balance (You): ${}", self.initial_balance),
+            format!("Final Balance (You):    ${}", self.final_balance),
+            format!("Highest Balance (You): ${}", self.highest_balance_session),
+            format!("Lowest Balance (You):  ${}", self.lowest_balance_session),
+            format!("Your Blackjacks: {}", self.blackjacks_dealt_player),
+        ]);
+        lines.extend(self.swings.to_log_lines(self.initial_default_bet).into_iter().take(2)); // Streaks and largest swings
+        lines
+    }
 
-        lines.push(format!("Total Script Runtime: {:.3} seconds", self.total_script_runtime_seconds));
-        lines.push(format!("Average Time per Main Hand (You): {:.4} seconds", self.avg_time_per_hand_seconds));
+    pub fn to_log_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Run ID: {}", self.run_id),
+            format!("Mode: {}", self.mode),
+        ];
+        if self.practice_mode {
+            lines.push(format!("Practice Session: Yes (undos: {}, redos: {}) - not real results", self.undos_used, self.redos_used));
+        }
 
         if let Some(iters) = self.target_iterations {
             lines.push(format!("Target Iterations: {}", iters));
         }
         lines.extend(vec![
             format!("Hands Played by You (Main): {}", self.hands_played_session),
-            format!("Starting Balance (You): ${:.2}", self.initial_balance),
-            format!("Final Balance (You):    ${:.2}", self.final_balance),
-            format!("Highest Balance (You): ${:.2}", self.highest_balance_session),
-            format!("Lowest Balance (You):  ${:.2}", self.lowest_balance_session),
-            format!("Default Bet Used (You): ${:.2}", self.initial_default_bet),
-            format!("Net Profit/Loss (You):  ${:+.2}", self.net_profit_loss),
+            format!("Starting Balance (You): ${}", self.initial_balance),
+            format!("Final Balance (You):    ${}", self.final_balance),
+            format!("Highest Balance (You): ${}", self.highest_balance_session),
+            format!("Lowest Balance (You):  ${}", self.lowest_balance_session),
+            format!("Default Bet Used (You): ${}", self.initial_default_bet),
+            format!("Net Profit/Loss (You):  ${:+}", self.net_profit_loss),
             format!("Avg. P/L per Main Hand (You): ${:+.2}", self.avg_earn_loss_per_main_hand),
             format!("Your Blackjacks: {}", self.blackjacks_dealt_player),
             format!("Your Times 'Split' Chosen: {}", self.times_split_chosen),
             format!("Your Original Hands Involving a Split: {}", self.hands_involved_in_split),
             format!("Your Total Individual Hands from Splits: {}", self.total_hands_after_splits),
-            format!("Your Net P/L from All Split Hand Parts: ${:+.2}", self.earnings_from_split_hands),
+            format!("Your Net P/L from All Split Hand Parts: ${:+}", self.earnings_from_split_hands),
             format!("Your Avg. P/L per Individual Split Hand Part: ${:+.2} (from {} parts)",
                     self.avg_earn_loss_per_split_hand_part, self.num_resolved_split_hands),
             format!("Your Times 'Double Down' Chosen: {}", self.times_doubled_chosen),
             format!("Your Hands Involving a Double Down: {}", self.hands_involved_in_double),
-            format!("Your Net P/L from Doubled Hands: ${:+.2}", self.earnings_from_doubled_hands),
+            format!("Your Net P/L from Doubled Hands: ${:+}", self.earnings_from_doubled_hands),
             format!("Your Avg. P/L per Doubled Hand: ${:+.2} (from {} hands)",
                     self.avg_earn_loss_per_doubled_hand, self.num_resolved_doubled_hands),
             format!("Your Total Wins: {}, Losses: {}, Pushes: {}",
                     self.total_wins, self.total_losses, self.total_pushes),
         ]);
-        if self.mode.contains("Simulation") {
-             lines.push(format!("Strategy for All AI Players: 'Book' (H17, {}D, DAS based)", config::NUM_DECKS));
+        if let Some((ev, low, high)) = self.ev_per_initial_bet_pct_with_ci() {
+            let bet = self.initial_default_bet.as_f64();
+            let std_dev_bets = self.round_results.std_dev().unwrap_or(0.0) / bet;
+            lines.push(format!("EV per Initial Bet (You): {:+.2}% +/- {:.2}% (95% CI: {:+.2}% to {:+.2}%, {} rounds)",
+                ev, (high - low) / 2.0, low, high, self.round_results.rounds));
+            lines.push(format!("Std. Dev. per Hand (You): {:.3} bets (variance {:.3}), Std. Error: {:.4} bets",
+                std_dev_bets, std_dev_bets * std_dev_bets, self.round_results.std_error().unwrap_or(0.0) / bet));
+            if let Some(rounds_needed) = self.round_results.rounds_for_precision(bet * config::TARGET_EV_PRECISION_PCT / 100.0) {
+                lines.push(format!("Hands Needed for +/- {:.2}% EV Precision (95%): {}", config::TARGET_EV_PRECISION_PCT, rounds_needed));
+            }
+        }
+        lines.extend(self.swings.to_log_lines(self.initial_default_bet));
+        if !self.ai_seat_profiles.is_empty() {
+             lines.push(format!("AI Seat Profiles: {} (Book = H17, {}D, DAS based)", self.ai_seat_profiles, config::settings().num_decks));
+        }
+        if self.ai_insurance_taken > 0 {
+            let lost = self.ai_insurance_taken - self.ai_insurance_won;
+            lines.push(format!("AI Insurance Bets: {} taken, {} won, net {:+} insurance bets",
+                self.ai_insurance_taken, self.ai_insurance_won, 2 * self.ai_insurance_won as i64 - lost as i64));
         }
 
         // Add timing information if they have been set (i.e., not 0.0)
@@ -158,7 +496,7 @@ impl SessionStats {
     }
 }
 
-//// Logging setup (can be moved to its own module or main.rs)
+// Logging setup (can be moved to its own module or main.rs)
 //pub fn setup_logger(run_id: u64) -> Result<(), Box<dyn std::error::Error>> {
 //    use crate::config::{LOGS_DIR_NAME, TEXT_LOG_FILENAME};
 //    use std::fs;
@@ -203,27 +541,220 @@ impl SessionStats {
 //}
 
 pub fn setup_logger(run_id: u64) -> Result<(), Box<dyn std::error::Error>> {
-    let logs_dir = PathBuf::from(LOGS_DIR_NAME);
-    fs::create_dir_all(&logs_dir)?;
-    let log_file_path = logs_dir.join(TEXT_LOG_FILENAME);
-
-    fern::Dispatch::new()
-        .format(move |out, message, record| { // move run_id into the closure
-            out.finish(format_args!(
-                "{} [{}] RUN_ID:{} - {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                record.level(),
-                run_id, // Use the captured run_id
-                message
-            ))
-        })
-        .level(log::LevelFilter::Info)
-        .chain( // Log to stdout (optional)
-            std::io::stdout()
-        )
-        .chain(fern::log_file(log_file_path)?) // Log to file (appends by default)
-        .apply()?; // Apply the logger globally
+    let settings = config::settings();
+    let log_file_path = settings.log_file_path(run_id);
+    if let Some(parent) = log_file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file_dispatch = match settings.log_format {
+        LogFormat::Text => fern::Dispatch::new().format(move |out, message, record| {
+            out.finish(format_args!("{}", text_log_line(run_id, message, record)))
+        }),
+        LogFormat::Json => fern::Dispatch::new().format(move |out, message, record| {
+            out.finish(format_args!("{}", serde_json::json!({
+                "timestamp": chrono::Local::now().to_rfc3339(),
+                "level": record.level().to_string(),
+                "run_id": run_id,
+                "target": record.target(),
+                "message": message.to_string(),
+            })))
+        }),
+    };
+    let mut dispatch = fern::Dispatch::new()
+        .level(settings.log_level)
+        .chain(file_dispatch.chain(fern::log_file(log_file_path)?)); // Log to file (appends by default)
+    if settings.log_to_stdout {
+        dispatch = dispatch.chain(fern::Dispatch::new()
+            .format(move |out, message, record| out.finish(format_args!("{}", text_log_line(run_id, message, record))))
+            .chain(std::io::stdout()));
+    }
+    dispatch.apply()?; // Apply the logger globally
 
     log::info!("Logger initialized."); // This will now use fern
     Ok(())
 }
+
+fn text_log_line(run_id: u64, message: &std::fmt::Arguments, record: &log::Record) -> String {
+    format!("{} [{}] RUN_ID:{} - {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), record.level(), run_id, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_results_of(dollars: &[i64]) -> RoundResultStats {
+        let mut stats = RoundResultStats::default();
+        for &d in dollars {
+            stats.add(Money::from_dollars(d));
+        }
+        stats
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    // Mean 5, sum of squared deviations 32
+    const KNOWN_SAMPLE: [i64; 8] = [2, 4, 4, 4, 5, 5, 7, 9];
+
+    #[test]
+    fn round_results_need_two_rounds_for_a_spread() {
+        let one = round_results_of(&[25]);
+        assert_close(one.mean, 25.0);
+        assert_eq!(one.variance(), None);
+        assert_eq!(one.std_error(), None);
+        assert_eq!(one.confidence_interval_95(), None);
+        assert_eq!(one.rounds_for_precision(1.0), None);
+    }
+
+    #[test]
+    fn round_results_match_the_sample_variance() {
+        let stats = round_results_of(&KNOWN_SAMPLE);
+        assert_eq!(stats.rounds, 8);
+        assert_close(stats.mean, 5.0);
+        assert_close(stats.variance().unwrap(), 32.0 / 7.0);
+        assert_close(stats.std_dev().unwrap(), (32.0f64 / 7.0).sqrt());
+        assert_close(stats.std_error().unwrap(), (32.0f64 / 7.0 / 8.0).sqrt());
+    }
+
+    #[test]
+    fn round_results_are_stable_far_from_zero() {
+        let shifted: Vec<i64> = KNOWN_SAMPLE.iter().map(|d| d + 10_000_000).collect();
+        let stats = round_results_of(&shifted);
+        assert_close(stats.mean, 10_000_005.0);
+        assert!((stats.variance().unwrap() - 32.0 / 7.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn round_results_confidence_interval_and_precision() {
+        let stats = round_results_of(&KNOWN_SAMPLE);
+        let half_width = 1.96 * (32.0f64 / 7.0 / 8.0).sqrt();
+        let (low, high) = stats.confidence_interval_95().unwrap();
+        assert_close(low, 5.0 - half_width);
+        assert_close(high, 5.0 + half_width);
+        // (1.96 * sd / 1)^2 = 3.8416 * 32 / 7 = 17.56...
+        assert_eq!(stats.rounds_for_precision(1.0), Some(18));
+        assert_eq!(stats.rounds_for_precision(0.0), None);
+    }
+
+    const BET: Money = Money::from_dollars(25);
+
+    fn swings_of(results: &[i64]) -> SwingStats {
+        let mut swings = SwingStats::default();
+        for (i, &bets) in results.iter().enumerate() {
+            swings.add_round(i as u32 + 1, BET * bets, BET);
+        }
+        swings
+    }
+
+    #[test]
+    fn streaks_skip_pushes_and_count_the_running_streak() {
+        // W W P W | L L | W (still running)
+        let swings = swings_of(&[1, 2, 0, 1, -1, -1, 1]);
+        assert_eq!(swings.longest_win_streak, 3);
+        assert_eq!(swings.longest_loss_streak, 2);
+        assert_eq!(swings.current_streak, 1);
+        assert_eq!((swings.decided_rounds, swings.rounds_won), (6, 4));
+        assert_eq!(swings.win_streaks, BTreeMap::from([(3, 1)]));
+        let (wins, losses) = swings.streak_counts();
+        assert_eq!(wins, BTreeMap::from([(1, 1), (3, 1)]));
+        assert_eq!(losses, BTreeMap::from([(2, 1)]));
+        assert_eq!(swings.largest_win, Some((2, BET * 2)));
+        assert_eq!(swings.largest_loss, Some((5, -BET)));
+    }
+
+    #[test]
+    fn streaks_of_one_alternate() {
+        let swings = swings_of(&[-1, 1, -1, 1]);
+        assert_eq!((swings.longest_win_streak, swings.longest_loss_streak), (1, 1));
+        let (wins, losses) = swings.streak_counts();
+        assert_eq!(wins, BTreeMap::from([(1, 2)]));
+        assert_eq!(losses, BTreeMap::from([(1, 2)]));
+    }
+
+    #[test]
+    fn shoes_split_at_each_reshuffle() {
+        let mut swings = swings_of(&[1, 1, -1]);
+        swings.start_new_shoe();
+        swings.start_new_shoe(); // Nothing played yet in the new shoe: no empty shoe is added
+        swings.add_round(4, -BET * 2, BET);
+        assert_eq!(swings.shoes.len(), 2);
+        assert_eq!((swings.shoes[0].rounds, swings.shoes[0].net_result), (3, BET));
+        assert_eq!(swings.winning_shoes(), 1);
+        assert_eq!(swings.worst_shoe(), Some(-BET * 2));
+    }
+
+    #[test]
+    fn expected_streaks_small_cases() {
+        assert_close(SwingStats::expected_streaks(1, 0.3, 1), 0.3);
+        assert_close(SwingStats::expected_streaks(2, 0.3, 1), 2.0 * 0.3 * 0.7); // SF or FS
+        assert_close(SwingStats::expected_streaks(2, 0.3, 2), 0.09);
+        assert_close(SwingStats::expected_streaks(3, 0.5, 1), 5.0 / 8.0); // SFF, FSF, FFS, SFS (2)
+        assert_close(SwingStats::expected_streaks(3, 0.5, 4), 0.0);
+    }
+
+    // Every sequence of `trials` outcomes, weighted by its probability
+    fn brute_force_expected_streaks(trials: u32, p: f64, length: u32) -> f64 {
+        (0..1u32 << trials).map(|outcomes| {
+            let successes = outcomes.count_ones() as i32;
+            let probability = p.powi(successes) * (1.0 - p).powi(trials as i32 - successes);
+            let mut runs = 0;
+            let mut run = 0;
+            for i in 0..=trials {
+                if i < trials && outcomes & (1 << i) != 0 {
+                    run += 1;
+                } else {
+                    if run == length { runs += 1; }
+                    run = 0;
+                }
+            }
+            probability * runs as f64
+        }).sum()
+    }
+
+    #[test]
+    fn expected_streaks_match_enumeration() {
+        for &p in &[0.3, 0.5, 0.8] {
+            for length in 1..=8 {
+                assert_close(SwingStats::expected_streaks(8, p, length), brute_force_expected_streaks(8, p, length));
+            }
+        }
+    }
+
+    #[test]
+    fn expected_streaks_account_for_every_success() {
+        let (trials, p) = (50, 0.47);
+        let successes: f64 = (1..=trials).map(|k| k as f64 * SwingStats::expected_streaks(trials, p, k)).sum();
+        assert!((successes - trials as f64 * p).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stop_win_and_stop_loss_count_the_threshold_reached_first() {
+        let mut swings = SwingStats { session_hands: 3, ..SwingStats::default() };
+        let (win, loss) = (config::STOP_WIN_BETS, config::STOP_LOSS_BETS);
+        let sessions: [[i64; 3]; 4] = [
+            [win, -2 * win - loss, 0], // Up first, then down past the stop-loss
+            [-loss, 2 * loss + win, 0], // Down first
+            [win - 1, 0, -1],           // Neither
+            [win, 0, 0],                // Only two rounds played: not a full session
+        ];
+        let mut round = 0;
+        for (i, results) in sessions.iter().enumerate() {
+            for &bets in results.iter().take(if i == 3 { 2 } else { 3 }) {
+                round += 1;
+                swings.add_round(round, BET * bets, BET);
+            }
+        }
+        assert_eq!(swings.stop_sessions, 3);
+        assert_eq!((swings.stop_win_first, swings.stop_loss_first), (1, 1));
+    }
+
+    #[test]
+    fn round_results_of_a_constant_series_have_no_spread() {
+        let stats = round_results_of(&[-25, -25, -25, -25]);
+        assert_close(stats.mean, -25.0);
+        assert_close(stats.variance().unwrap(), 0.0);
+        assert_eq!(stats.confidence_interval_95(), Some((-25.0, -25.0)));
+    }
+}
@@ -1,7 +1,10 @@
 // src/strategy.rs
 use crate::card_deck::Rank;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlayerAction {
     Hit,
     Stand,
@@ -10,6 +13,110 @@ pub enum PlayerAction {
     // Surrender, // Not implemented in this version
 }
 
+impl fmt::Display for PlayerAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerAction::Hit => write!(f, "Hit"),
+            PlayerAction::Stand => write!(f, "Stand"),
+            PlayerAction::Double => write!(f, "Double Down"),
+            PlayerAction::Split => write!(f, "Split"),
+        }
+    }
+}
+
+// Falls back from a Split or Double that isn't allowed right now (funds, split limit)
+// to the next best play for the hand.
+pub fn restrict_to_allowed_action(
+    action: PlayerAction,
+    p_hand_cards: &[crate::card_deck::Card],
+    dealer_upcard_value: u8,
+    can_double: bool,
+    can_split: bool,
+    simulation_active: bool,
+) -> PlayerAction {
+    let mut allowed_action = action;
+    if allowed_action == PlayerAction::Split && !can_split {
+        allowed_action = get_basic_strategy_action(p_hand_cards, dealer_upcard_value, 10, simulation_active);
+        if allowed_action == PlayerAction::Split { allowed_action = PlayerAction::Hit; }
+    }
+    if allowed_action == PlayerAction::Double && !can_double { allowed_action = PlayerAction::Hit; }
+    allowed_action
+}
+
+// How an AI seat plays its hands. Only "Book" matches the chart below;
+// the others model common real-table behaviour.
+// Picked per seat in config::AI_SEAT_PROFILES or with --ai-seat
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AiProfile {
+    Book,
+    NeverBust,                   // Stands on any hard 12+, never doubles or splits
+    MimicDealer,                 // Hits below 17, stands on 17+, never doubles or splits
+    Sloppy { error_rate: f64 },  // Book, but makes a random other play with this probability
+    Tourist,                     // Book, but always takes insurance
+}
+
+impl AiProfile {
+    pub fn takes_insurance(&self) -> bool {
+        matches!(self, AiProfile::Tourist)
+    }
+}
+
+impl fmt::Display for AiProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AiProfile::Book => write!(f, "Book"),
+            AiProfile::NeverBust => write!(f, "Never Bust"),
+            AiProfile::MimicDealer => write!(f, "Mimic Dealer"),
+            AiProfile::Sloppy { error_rate } => write!(f, "Sloppy {:.0}%", error_rate * 100.0),
+            AiProfile::Tourist => write!(f, "Tourist"),
+        }
+    }
+}
+
+// Action for an AI seat according to its profile. Legality (funds, split limit)
+// is still enforced by the caller, the same as for book plays; a Sloppy seat's
+// random errors are only drawn from the plays allowed right now.
+pub fn get_ai_profile_action<R: Rng>(
+    profile: AiProfile,
+    p_hand_cards: &[crate::card_deck::Card],
+    dealer_upcard_value: u8,
+    num_total_player_hands_for_this_player: usize,
+    can_double: bool,
+    can_split: bool,
+    rng: &mut R,
+) -> PlayerAction {
+    let player_value = calculate_value_for_strategy(p_hand_cards);
+    let is_soft = is_soft_for_strategy(p_hand_cards);
+    match profile {
+        AiProfile::Book | AiProfile::Tourist => get_basic_strategy_action(
+            p_hand_cards, dealer_upcard_value, num_total_player_hands_for_this_player, false,
+        ),
+        AiProfile::NeverBust => {
+            // A soft hand cannot bust on one card, so keep drawing to soft 18
+            if (is_soft && player_value < 18) || (!is_soft && player_value < 12) { PlayerAction::Hit }
+            else { PlayerAction::Stand }
+        }
+        AiProfile::MimicDealer => {
+            if player_value < 17 { PlayerAction::Hit } else { PlayerAction::Stand }
+        }
+        AiProfile::Sloppy { error_rate } => {
+            let book_action = get_basic_strategy_action(
+                p_hand_cards, dealer_upcard_value, num_total_player_hands_for_this_player, false,
+            );
+            if rng.gen_bool(error_rate.clamp(0.0, 1.0)) {
+                let alternatives: Vec<PlayerAction> = [PlayerAction::Hit, PlayerAction::Stand, PlayerAction::Double, PlayerAction::Split]
+                    .into_iter()
+                    .filter(|&a| a != book_action)
+                    .filter(|&a| (a != PlayerAction::Double || can_double) && (a != PlayerAction::Split || can_split))
+                    .collect();
+                alternatives[rng.gen_range(0..alternatives.len())]
+            } else {
+                book_action
+            }
+        }
+    }
+}
+
 // Basic Strategy implementation (Simplified for H17, 6D, DAS)
 // p_hand_cards: The cards of the specific hand being evaluated
 // dealer_upcard_value: The numerical value of the dealer's upcard (Ace=11 for this)
@@ -32,82 +139,73 @@ pub fn get_basic_strategy_action(
         let card_rank = p_hand_cards[0].rank; // Both cards have same rank if is_pair
         match card_rank {
             Rank::Ace | Rank::Eight => return PlayerAction::Split,
-            Rank::Nine => {
-                if ![7, 10, 11].contains(&dealer_upcard_value) { return PlayerAction::Split; }
-            }
-            Rank::Seven => {
-                if dealer_upcard_value <= 7 { return PlayerAction::Split; }
-            }
-            Rank::Six => {
-                if dealer_upcard_value <= 6 { return PlayerAction::Split; } // DAS assumed
-            }
-            Rank::Four => { // Only split 4s if DAS, and vs 5,6
-                if [5, 6].contains(&dealer_upcard_value) { return PlayerAction::Split; } // DAS assumed
-            }
-            Rank::Three | Rank::Two => {
-                if dealer_upcard_value <= 7 { return PlayerAction::Split; } // DAS assumed
-            }
+            Rank::Nine if ![7, 10, 11].contains(&dealer_upcard_value) => return PlayerAction::Split,
+            Rank::Seven if dealer_upcard_value <= 7 => return PlayerAction::Split,
+            Rank::Six if dealer_upcard_value <= 6 => return PlayerAction::Split, // DAS assumed
+            // Only split 4s if DAS, and vs 5,6
+            Rank::Four if [5, 6].contains(&dealer_upcard_value) => return PlayerAction::Split, // DAS assumed
+            Rank::Three | Rank::Two if dealer_upcard_value <= 7 => return PlayerAction::Split, // DAS assumed
             _ => {} // No split for 5s or 10-value cards by default strategy (5,5 is hard 10)
         }
     }
 
     // SOFT HANDS (Ace counted as 11)
     if is_soft {
-        match player_value {
-            19..=21 => return PlayerAction::Stand, // Soft 19-21 Stand
+        return match player_value {
+            19..=21 => PlayerAction::Stand, // Soft 19-21 Stand
             18 => { // Soft 18 (A,7)
-                if dealer_upcard_value <= 6 && can_double_check { return PlayerAction::Double; }
-                else if [2, 7, 8].contains(&dealer_upcard_value) { return PlayerAction::Stand; }
-                else { return PlayerAction::Hit; }
+                if dealer_upcard_value <= 6 && can_double_check { PlayerAction::Double }
+                else if [2, 7, 8].contains(&dealer_upcard_value) { PlayerAction::Stand }
+                else { PlayerAction::Hit }
             }
             17 => { // Soft 17 (A,6)
-                if (3..=6).contains(&dealer_upcard_value) && can_double_check { return PlayerAction::Double; }
-                else { return PlayerAction::Hit; }
+                if (3..=6).contains(&dealer_upcard_value) && can_double_check { PlayerAction::Double }
+                else { PlayerAction::Hit }
             }
             16 => { // Soft 16 (A,5)
-                if (4..=6).contains(&dealer_upcard_value) && can_double_check { return PlayerAction::Double; }
-                else { return PlayerAction::Hit; }
+                if (4..=6).contains(&dealer_upcard_value) && can_double_check { PlayerAction::Double }
+                else { PlayerAction::Hit }
             }
             15 => { // Soft 15 (A,4)
-                if (4..=6).contains(&dealer_upcard_value) && can_double_check { return PlayerAction::Double; }
-                else { return PlayerAction::Hit; }
+                if (4..=6).contains(&dealer_upcard_value) && can_double_check { PlayerAction::Double }
+                else { PlayerAction::Hit }
             }
             13 | 14 => { // Soft 13/14 (A,2/A,3)
-                if (5..=6).contains(&dealer_upcard_value) && can_double_check { return PlayerAction::Double; }
-                else { return PlayerAction::Hit; }
+                if (5..=6).contains(&dealer_upcard_value) && can_double_check { PlayerAction::Double }
+                else { PlayerAction::Hit }
             }
-            _ => return PlayerAction::Hit, // Soft 12 or less (should not happen if Ace is 11) or other unhandled
-        }
+            _ => PlayerAction::Hit, // Soft 12 or less (should not happen if Ace is 11) or other unhandled
+        };
     }
 
     // HARD HANDS
     match player_value {
-        17..=21 => return PlayerAction::Stand,
+        17..=21 => PlayerAction::Stand,
         13..=16 => {
-            if dealer_upcard_value <= 6 { return PlayerAction::Stand; }
-            else { return PlayerAction::Hit; }
+            if dealer_upcard_value <= 6 { PlayerAction::Stand }
+            else { PlayerAction::Hit }
         }
         12 => {
-            if (4..=6).contains(&dealer_upcard_value) { return PlayerAction::Stand; }
-            else { return PlayerAction::Hit; }
+            if (4..=6).contains(&dealer_upcard_value) { PlayerAction::Stand }
+            else { PlayerAction::Hit }
         }
         11 => {
-            if can_double_check { return PlayerAction::Double; } else { return PlayerAction::Hit; }
+            if can_double_check { PlayerAction::Double } else { PlayerAction::Hit }
         }
         10 => {
-            if dealer_upcard_value <= 9 && can_double_check { return PlayerAction::Double; }
-            else { return PlayerAction::Hit; }
+            if dealer_upcard_value <= 9 && can_double_check { PlayerAction::Double }
+            else { PlayerAction::Hit }
         }
         9 => {
-            if (2..=6).contains(&dealer_upcard_value) && can_double_check { return PlayerAction::Double; }
-            else { return PlayerAction::Hit; }
+            if (2..=6).contains(&dealer_upcard_value) && can_double_check { PlayerAction::Double }
+            else { PlayerAction::Hit }
         }
-        _ => return PlayerAction::Hit, // Hard 8 or less
+        _ => PlayerAction::Hit, // Hard 8 or less
     }
 }
 
 // Helper: Calculate value specifically for strategy (like Hand::value)
-fn calculate_value_for_strategy(cards: &[crate::card_deck::Card]) -> u8 {
+pub fn calculate_value_for_strategy(cards: &[crate::card_deck::Card]) -> u8 {
     let mut total_value = 0u8;
     let mut num_aces = 0u8;
     for card in cards {
@@ -123,7 +221,7 @@ fn calculate_value_for_strategy(cards: &[crate::card_deck::Card]) -> u8 {
 }
 
 // Helper: Check if soft specifically for strategy (like Hand::is_soft)
-fn is_soft_for_strategy(cards: &[crate::card_deck::Card]) -> bool {
+pub fn is_soft_for_strategy(cards: &[crate::card_deck::Card]) -> bool {
     let mut non_ace_value = 0u8;
     let mut num_aces = 0u8;
     for card in cards {
@@ -134,5 +232,5 @@ fn is_soft_for_strategy(cards: &[crate::card_deck::Card]) -> bool {
         }
     }
     if num_aces == 0 { return false; }
-    (non_ace_value as u16 + (num_aces as u16 - 1) * 1 + 11) <= 21
+    (non_ace_value as u16 + (num_aces as u16 - 1) + 11) <= 21
 }
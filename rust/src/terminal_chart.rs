@@ -0,0 +1,75 @@
+// src/terminal_chart.rs
+use crate::graph::{half_bet_counts, round_results_in_bets};
+
+// Text versions of the balance graph and round result histogram, for terminals over SSH
+
+const LABEL_WIDTH: usize = 11;
+
+// Balance after each hand, squeezed to `width` columns. Each column spans the lowest to highest
+// balance of the hands it covers; "┄" marks the starting balance.
+pub fn balance_chart_lines(balance_history: &[f64], starting_balance: f64, width: usize, height: usize) -> Vec<String> {
+    if balance_history.len() < 2 || width == 0 || height < 2 {
+        return vec!["Not enough hands for a balance chart.".to_string()];
+    }
+    let num_points = balance_history.len();
+    let columns = width.min(num_points);
+    let min = balance_history.iter().copied().fold(starting_balance, f64::min);
+    let max = balance_history.iter().copied().fold(starting_balance, f64::max);
+    let (min, max) = if max - min < 1.0 { (min - 1.0, max + 1.0) } else { (min, max) };
+    // Row 0 is the top of the chart
+    let row_of = |value: f64| (((max - value) / (max - min)) * (height - 1) as f64).round() as usize;
+
+    let mut grid = vec![vec![' '; columns]; height];
+    let start_row = row_of(starting_balance);
+    for cell in grid[start_row].iter_mut() {
+        *cell = '┄';
+    }
+    for column in 0..columns {
+        let first = column * num_points / columns;
+        let bucket = &balance_history[first..((column + 1) * num_points / columns).max(first + 1)];
+        let low = bucket.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = bucket.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        for row in grid.iter_mut().take(row_of(low) + 1).skip(row_of(high)) {
+            row[column] = '█';
+        }
+    }
+
+    let final_balance = balance_history[num_points - 1];
+    let mut lines = vec![format!("--- Balance over {} Hands (start ${:.2}, end ${:.2}, ┄ = starting balance) ---",
+        num_points - 1, starting_balance, final_balance)];
+    for (row, cells) in grid.iter().enumerate() {
+        let label = if row == 0 {
+            format!("${:.0}", max)
+        } else if row == height - 1 {
+            format!("${:.0}", min)
+        } else if row == start_row {
+            format!("${:.0}", starting_balance)
+        } else {
+            String::new()
+        };
+        let axis = if label.is_empty() { '│' } else { '┤' };
+        lines.push(format!("{:>w$} {}{}", label, axis, cells.iter().collect::<String>(), w = LABEL_WIDTH));
+    }
+    lines.push(format!("{:>w$} └{}", "", "─".repeat(columns), w = LABEL_WIDTH));
+    lines.push(format!("{:>w$}  0{:>c$}", "", num_points - 1, w = LABEL_WIDTH, c = columns - 1));
+    lines
+}
+
+// Horizontal bars of the net result per round, one per half bet as in the PNG/SVG histogram
+pub fn round_result_histogram_lines(balance_history: &[f64], bet: f64, width: usize) -> Vec<String> {
+    let results = round_results_in_bets(balance_history, bet);
+    if results.is_empty() {
+        return vec!["No rounds for a round result histogram.".to_string()];
+    }
+    let counts = half_bet_counts(&results);
+    let max_count = counts.values().copied().max().unwrap_or(1).max(1);
+    let bar_width = width.saturating_sub(16).max(10); // Room for the count and share after the bar
+    let mut lines = vec![format!("--- Net Result per Round ({} rounds, in bets) ---", results.len())];
+    for (half_bets, count) in counts {
+        let bar_length = ((count as f64 / max_count as f64) * bar_width as f64).round().max(1.0) as usize;
+        lines.push(format!("{:>w$} │{:<b$} {:>5} ({:>4.1}%)",
+            format!("{:+.1}", half_bets as f64 / 2.0), "█".repeat(bar_length), count,
+            count as f64 / results.len() as f64 * 100.0, w = LABEL_WIDTH, b = bar_width));
+    }
+    lines
+}
@@ -0,0 +1,238 @@
+// src/trainer.rs
+use crate::card_deck::{Card, Rank, ALL_RANKS, ALL_SUITS};
+use crate::strategy::{
+    calculate_value_for_strategy, get_basic_strategy_action, is_soft_for_strategy, PlayerAction,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Strategy chart section a hand is looked up in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum HandKind {
+    Hard,
+    Soft,
+    Pair,
+}
+
+impl fmt::Display for HandKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HandKind::Hard => write!(f, "Hard"),
+            HandKind::Soft => write!(f, "Soft"),
+            HandKind::Pair => write!(f, "Pair"),
+        }
+    }
+}
+
+pub fn classify_hand(cards: &[Card]) -> HandKind {
+    if cards.len() == 2 && cards[0].rank.blackjack_value() == cards[1].rank.blackjack_value() {
+        HandKind::Pair
+    } else if is_soft_for_strategy(cards) {
+        HandKind::Soft
+    } else {
+        HandKind::Hard
+    }
+}
+
+// Upcard value as used by the strategy chart: 2-10, Ace = 11
+pub fn upcard_label(upcard_value: u8) -> String {
+    if upcard_value == 11 { "A".to_string() } else { upcard_value.to_string() }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainerMistake {
+    pub player_cards: Vec<Card>,
+    pub dealer_upcard: Card,
+    pub chosen: PlayerAction,
+    pub book: PlayerAction,
+    pub ev_cost_bets: f64, // EV(book) - EV(chosen), in units of this hand's bet; may be slightly negative on near-ties
+    #[serde(default)]
+    pub ev_cost_std_error: f64, // Sampling error of ev_cost_bets, in bets
+    pub bet: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrainerStats {
+    // (kind, dealer upcard value) -> (correct decisions, total decisions)
+    #[serde(with = "per_cell_entries")]
+    pub per_cell: BTreeMap<(HandKind, u8), (u32, u32)>,
+    pub mistakes: Vec<TrainerMistake>,
+}
+
+// JSON object keys must be strings, so the per-cell map is saved as a list of entries
+mod per_cell_entries {
+    use super::HandKind;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    type PerCell = BTreeMap<(HandKind, u8), (u32, u32)>;
+
+    pub fn serialize<S: Serializer>(per_cell: &PerCell, serializer: S) -> Result<S::Ok, S::Error> {
+        per_cell.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PerCell, D::Error> {
+        Ok(Vec::<((HandKind, u8), (u32, u32))>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+impl TrainerStats {
+    pub fn record_decision(&mut self, cards: &[Card], dealer_upcard_value: u8, correct: bool) {
+        let cell = self.per_cell.entry((classify_hand(cards), dealer_upcard_value)).or_insert((0, 0));
+        if correct { cell.0 += 1; }
+        cell.1 += 1;
+    }
+
+    pub fn total_decisions(&self) -> u32 {
+        self.per_cell.values().map(|&(_, total)| total).sum()
+    }
+
+    pub fn total_correct(&self) -> u32 {
+        self.per_cell.values().map(|&(correct, _)| correct).sum()
+    }
+
+    pub fn total_ev_cost_dollars(&self) -> f64 {
+        self.mistakes.iter().fold(0.0, |acc, m| acc + m.ev_cost_bets * m.bet)
+    }
+
+    pub fn to_log_lines(&self) -> Vec<String> {
+        let total = self.total_decisions();
+        let correct = self.total_correct();
+        let accuracy = if total > 0 { correct as f64 / total as f64 * 100.0 } else { 0.0 };
+        let mut lines = vec![
+            "--- Trainer Report ---".to_string(),
+            format!("Decisions Graded: {}, Matched Book: {} ({:.1}%)", total, correct, accuracy),
+            format!("Estimated EV Cost of Mistakes (infinite-shoe estimate): ${:.2} ({:.3} bets)",
+                self.total_ev_cost_dollars(), self.mistakes.iter().fold(0.0, |acc, m| acc + m.ev_cost_bets)),
+        ];
+        for kind in [HandKind::Hard, HandKind::Soft, HandKind::Pair] {
+            let cells: Vec<String> = (2..=11u8)
+                .filter_map(|up| self.per_cell.get(&(kind, up)).map(|&(c, t)| format!("{}:{}/{}", upcard_label(up), c, t)))
+                .collect();
+            if !cells.is_empty() {
+                lines.push(format!("Accuracy {} vs Upcard: {}", kind, cells.join("  ")));
+            }
+        }
+        for (i, m) in self.mistakes.iter().enumerate() {
+            let cards_str: Vec<String> = m.player_cards.iter().map(|c| c.to_string()).collect();
+            lines.push(format!("Mistake {}: {} vs {} - chose {}, Book: {} (est. cost {:.3} +/- {:.3} bets, ${:.2})",
+                i + 1, cards_str.join(" "), m.dealer_upcard, m.chosen, m.book, m.ev_cost_bets, m.ev_cost_std_error, m.ev_cost_bets * m.bet));
+        }
+        lines
+    }
+}
+
+// Monte Carlo estimate of what choosing `chosen` instead of `book` costs, in units of the
+// hand's bet, with its standard error. Each trial plays both actions (and the Book afterwards)
+// on the same cards, so the difference is far less noisy than two separate estimates.
+// Assumes an infinite shoe: cards are drawn with replacement, ignoring what has been dealt.
+// The dealer draws to 17 like the table does. The RNG is seeded from `seed` and the decision,
+// so the same decision is always given the same cost.
+pub fn estimate_ev_cost(player_cards: &[Card], dealer_upcard: Card, book: PlayerAction, chosen: PlayerAction,
+                        trials: u32, seed: u64) -> (f64, f64) {
+    let mut rng = ChaCha8Rng::seed_from_u64(decision_seed(seed, player_cards, dealer_upcard, book, chosen));
+    let (mut mean, mut m2) = (0.0, 0.0); // Welford, as in stats::RoundResultStats
+    for trial in 1..=trials {
+        let trial_seed: u64 = rng.gen();
+        let book_units = play_action_once(player_cards, dealer_upcard, book, &mut ChaCha8Rng::seed_from_u64(trial_seed));
+        let chosen_units = play_action_once(player_cards, dealer_upcard, chosen, &mut ChaCha8Rng::seed_from_u64(trial_seed));
+        let cost = book_units - chosen_units;
+        let delta = cost - mean;
+        mean += delta / trial as f64;
+        m2 += delta * (cost - mean);
+    }
+    let std_error = if trials > 1 { (m2 / (trials - 1) as f64 / trials as f64).sqrt() } else { 0.0 };
+    (mean, std_error)
+}
+
+// Folds the decision into `seed` with a fixed mix (SplitMix64's finalizer), so the seed does not
+// depend on the Rust release or platform the way std's hashers may
+fn decision_seed(seed: u64, player_cards: &[Card], dealer_upcard: Card, book: PlayerAction, chosen: PlayerAction) -> u64 {
+    let mix = |state: u64, value: u64| {
+        let mut z = (state ^ value).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    let card_code = |card: Card| card.rank as u64 * 4 + card.suit as u64;
+    let mut state = mix(seed, player_cards.len() as u64);
+    for &card in player_cards {
+        state = mix(state, card_code(card));
+    }
+    state = mix(state, card_code(dealer_upcard));
+    state = mix(state, book as u64);
+    mix(state, chosen as u64)
+}
+
+// One trial of taking `action` now and playing on by the Book: the net result in bets
+fn play_action_once(player_cards: &[Card], dealer_upcard: Card, action: PlayerAction, rng: &mut impl Rng) -> f64 {
+    let upcard_value = dealer_upcard.rank.value().0;
+    let mut dealer_cards = vec![dealer_upcard, random_card(rng)];
+    // (final hand value, bet multiplier) for each hand this action results in
+    let mut results: Vec<(u8, f64)> = Vec::new();
+    match action {
+        PlayerAction::Stand => results.push((calculate_value_for_strategy(player_cards), 1.0)),
+        PlayerAction::Hit => {
+            let mut cards = player_cards.to_vec();
+            cards.push(random_card(rng));
+            results.push(play_out_by_book(cards, upcard_value, false, rng));
+        }
+        PlayerAction::Double => {
+            let mut cards = player_cards.to_vec();
+            cards.push(random_card(rng));
+            results.push((calculate_value_for_strategy(&cards), 2.0));
+        }
+        PlayerAction::Split => {
+            let is_ace_split = player_cards[0].rank == Rank::Ace;
+            for &split_card in player_cards.iter().take(2) {
+                let cards = vec![split_card, random_card(rng)];
+                if is_ace_split {
+                    results.push((calculate_value_for_strategy(&cards), 1.0));
+                } else {
+                    results.push(play_out_by_book(cards, upcard_value, true, rng));
+                }
+            }
+        }
+    }
+    if results.iter().any(|&(value, _)| value <= 21) {
+        while calculate_value_for_strategy(&dealer_cards) < 17 {
+            dealer_cards.push(random_card(rng));
+        }
+    }
+    let dealer_value = calculate_value_for_strategy(&dealer_cards);
+    results.iter().map(|&(value, multiplier)| {
+        let units = if value > 21 { -1.0 }
+                    else if dealer_value > 21 || value > dealer_value { 1.0 }
+                    else if value < dealer_value { -1.0 }
+                    else { 0.0 };
+        units * multiplier
+    }).sum()
+}
+
+// Plays a hand on by the Book (no further splits). Returns (final value, bet multiplier).
+fn play_out_by_book(mut cards: Vec<Card>, upcard_value: u8, allow_double: bool, rng: &mut impl Rng) -> (u8, f64) {
+    loop {
+        let value = calculate_value_for_strategy(&cards);
+        if value >= 21 {
+            return (value, 1.0);
+        }
+        match get_basic_strategy_action(&cards, upcard_value, 4, true) {
+            PlayerAction::Stand => return (value, 1.0),
+            PlayerAction::Double if allow_double && cards.len() == 2 => {
+                cards.push(random_card(rng));
+                return (calculate_value_for_strategy(&cards), 2.0);
+            }
+            _ => cards.push(random_card(rng)),
+        }
+    }
+}
+
+pub fn random_card(rng: &mut impl Rng) -> Card {
+    Card {
+        rank: ALL_RANKS[rng.gen_range(0..ALL_RANKS.len())],
+        suit: ALL_SUITS[rng.gen_range(0..ALL_SUITS.len())],
+    }
+}
@@ -7,13 +7,19 @@ use std::time::Duration;
 use crate::player::{Player, Dealer}; // Assuming player.rs defines Player and Dealer
 use crate::hand::HandStatus;         // Assuming hand.rs defines HandStatus
 use crate::card_deck::Rank;          // Assuming card_deck.rs defines Rank
+use crate::money::Money;
 
 pub fn get_user_input(prompt: &str) -> String {
+    get_user_input_keep_case(prompt).to_lowercase()
+}
+
+// For file paths and other input where case matters
+pub fn get_user_input_keep_case(prompt: &str) -> String {
     print!("{}", prompt);
     io::stdout().flush().unwrap();
     let mut input = String::new();
     io::stdin().read_line(&mut input).expect("Failed to read line");
-    input.trim().to_lowercase()
+    input.trim().to_string()
 }
 
 pub fn get_delay_multiplied(base_millis: u64, simulation_active: bool) -> u64 {
@@ -47,36 +53,52 @@ pub fn get_num_iterations(default_iterations: u32) -> u32 {
     }
 }
 
-pub fn get_your_bet_from_input(current_balance: f64, default_bet: f64, min_bet: f64) -> Option<f64> {
+pub fn get_positive_number_input(label: &str, default_value: u32) -> u32 {
+    loop {
+        let input_str = get_user_input(&format!("{} (or press Enter for default {}): ", label, default_value));
+        if input_str.is_empty() {
+            return default_value;
+        }
+        match input_str.parse::<u32>() {
+            Ok(num) if num > 0 => return num,
+            Ok(_) => println!("Please enter a positive number."),
+            Err(_) => println!("Invalid input. Please enter a number."),
+        }
+    }
+}
+
+pub fn get_your_bet_from_input(current_balance: Money, default_bet: Money, min_bet: Money, chip_unit: Money) -> Option<Money> {
     if current_balance < min_bet {
         println!(
-            "Your balance (${:.2}) is too low to place any bet (min: ${:.2}).",
+            "Your balance (${}) is too low to place any bet (min: ${}).",
             current_balance, min_bet
         );
         return None;
     }
     loop {
         let input_str = get_user_input(&format!(
-            "Your balance: ${:.2}. Enter bet (or press Enter for default ${:.2}): ",
+            "Your balance: ${}. Enter bet (or press Enter for default ${}): ",
             current_balance, default_bet
         ));
         let bet_amount = if input_str.is_empty() {
             default_bet
         } else {
-            match input_str.parse::<f64>() {
-                Ok(b) => b,
-                Err(_) => {
-                    println!("Invalid input. Please enter a number or press Enter.");
+            match Money::parse(&input_str) {
+                Some(b) => b,
+                None => {
+                    println!("Invalid input. Please enter an amount (e.g. 25 or 12.50) or press Enter.");
                     continue;
                 }
             }
         };
 
         if bet_amount < min_bet {
-            println!("Bet must be at least ${:.2}.", min_bet);
+            println!("Bet must be at least ${}.", min_bet);
+        } else if !bet_amount.is_multiple_of(chip_unit) {
+            println!("Bet must be made in chips: a multiple of ${}.", chip_unit);
         } else if bet_amount > current_balance {
             println!(
-                "You cannot bet more than your current balance (${:.2}).",
+                "You cannot bet more than your current balance (${}).",
                 current_balance
             );
         } else {
@@ -84,7 +106,7 @@ pub fn get_your_bet_from_input(current_balance: f64, default_bet: f64, min_bet:
         }
         if input_str.is_empty() && default_bet > current_balance {
              println!(
-                "Default bet (${:.2}) is higher than your current balance (${:.2}). Please enter a valid amount.",
+                "Default bet (${}) is higher than your current balance (${}). Please enter a valid amount.",
                 default_bet, current_balance
             );
         }
@@ -124,7 +146,7 @@ pub fn display_your_hands_and_dealer(
         let cards_str: String = p_hand.cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(", ");
         let active_marker = if p_hand.status == HandStatus::Active && your_player_obj.is_user { "*" } else { " " }; // HandStatus in scope
         println!(
-            "{}{} Hand {}: {} (Value: {}) Bet: ${:.2} [{}]",
+            "{}{} Hand {}: {} (Value: {}) Bet: ${} [{}]",
             active_marker,
             your_player_obj.name,
             i + 1,